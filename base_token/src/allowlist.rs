@@ -0,0 +1,86 @@
+use near_sdk::json_types::{Base64VecU8, U128};
+use near_sdk::{env, near, AccountId};
+
+/// Proof accepted for an account, it only counts while the root of the allowlist is the same.
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct AllowlistEntry {
+    pub root: Base64VecU8,
+    pub cap: Option<U128>, // most tokens the account can bid for across all its orders
+}
+
+pub fn assert_valid_root(root: &Base64VecU8) {
+    assert!(root.0.len() == 32, "Allowlist root must be a sha256 hash");
+}
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+/// Leaf of the allowlist tree: sha256 of a 0 byte, the length of the account id as a little endian
+/// u32 and the account id, then a 0 byte without a cap or a 1 byte followed by the cap as a little
+/// endian u128. The prefixes keep a leaf from being read as an inner node or as another leaf.
+pub fn allowlist_leaf(account_id: &AccountId, cap: Option<U128>) -> Vec<u8> {
+    let mut bytes = vec![LEAF_PREFIX];
+    bytes.extend_from_slice(&(account_id.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(account_id.as_bytes());
+    match cap {
+        Some(cap) => {
+            bytes.push(1);
+            bytes.extend_from_slice(&cap.0.to_le_bytes());
+        }
+        None => bytes.push(0),
+    }
+    env::sha256(&bytes)
+}
+
+/// Inner node of the allowlist tree: sha256 of a 1 byte followed by both children in sorted order,
+/// so a proof is only the list of siblings.
+pub fn allowlist_node(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    env::sha256(&[&[NODE_PREFIX], first, second].concat())
+}
+
+/// Walks the proof from the leaf to the root.
+pub fn verify_proof(root: &[u8], leaf: Vec<u8>, proof: &[Base64VecU8]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf, |node, sibling| allowlist_node(&node, &sibling.0));
+    computed == root
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_verify_proof() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        let alice_leaf = allowlist_leaf(&alice, None);
+        let bob_leaf = allowlist_leaf(&bob, Some(U128(100)));
+        let root = allowlist_node(&alice_leaf, &bob_leaf);
+
+        assert!(verify_proof(
+            &root,
+            alice_leaf.clone(),
+            &[Base64VecU8(bob_leaf.clone())]
+        ));
+        assert!(verify_proof(
+            &root,
+            bob_leaf,
+            &[Base64VecU8(alice_leaf.clone())]
+        ));
+        // The cap is part of the leaf
+        assert!(!verify_proof(
+            &root,
+            allowlist_leaf(&bob, Some(U128(1_000))),
+            &[Base64VecU8(alice_leaf)]
+        ));
+        // A cap can not be read as the end of a longer account id
+        let cap = U128(u128::from_le_bytes(*b".near00000000000"));
+        assert_ne!(
+            allowlist_leaf(&"alice".parse().unwrap(), Some(cap)),
+            allowlist_leaf(&"alice.near00000000000".parse().unwrap(), None)
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use crate::price::U256;
+use near_sdk::json_types::U128;
+use near_sdk::{near, NearToken};
+
+/// Maximum number of times the price of an exponential curve can double over the supply.
+pub const MAX_DOUBLINGS: u128 = 32;
+
+/// Bonding curve mode: tokens are bought from and sold back to the contract at a price that
+/// depends on the tokens already sold, against the NEAR reserve held by the contract.
+#[near(serializers = [json, borsh])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BondingCurve {
+    pub start_buy_amount: NearToken, // near amount for all tokens at the price of the first token
+    pub shape: CurveShape,
+}
+
+#[near(serializers = [json, borsh])]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveShape {
+    Linear { end_buy_amount: NearToken }, // near amount for all tokens at the price of the last token
+    Exponential { doubling_amount: U128 }, // the price doubles every doubling_amount tokens sold
+}
+
+impl BondingCurve {
+    /// Near paid for the tokens between `from` and `to` sold on a curve of `supply` tokens, the
+    /// area under the curve. Buys round up and sells round down so the reserve always covers the
+    /// tokens sold.
+    pub fn cost(&self, supply: U128, from: u128, to: u128, round_up: bool) -> NearToken {
+        let supply = U256::from(supply.0);
+        let start = U256::from(self.start_buy_amount.as_yoctonear());
+        let amount = U256::from(to - from);
+
+        let cost = match self.shape {
+            // start + (end - start) * s / supply for the whole supply at s tokens sold
+            CurveShape::Linear { end_buy_amount } => {
+                let spread = U256::from(end_buy_amount.as_yoctonear()) - start;
+                let base = mul_div(start, amount, supply, round_up);
+                let slope = mul_div(spread, amount, supply, round_up);
+                base + mul_div(
+                    slope,
+                    U256::from(from) + U256::from(to),
+                    supply * 2,
+                    round_up,
+                )
+            }
+            // Linear between every doubling, so each segment is priced like a linear curve
+            CurveShape::Exponential { doubling_amount } => {
+                let doubling_amount = doubling_amount.0;
+                let mut cost = U256::zero();
+                let mut position = from;
+                while position < to {
+                    let segment = position / doubling_amount;
+                    let segment_start = segment * doubling_amount;
+                    let segment_end = (segment_start + doubling_amount).min(to);
+                    let lower = U256::from(position - segment_start);
+                    let upper = U256::from(segment_end - segment_start);
+
+                    let base = mul_div(start << segment as usize, upper - lower, supply, round_up);
+                    cost = cost
+                        + base
+                        + mul_div(
+                            base,
+                            lower + upper,
+                            U256::from(doubling_amount) * 2,
+                            round_up,
+                        );
+                    position = segment_end;
+                }
+                cost
+            }
+        };
+
+        assert!(cost <= U256::from(u128::MAX), "Math overflow");
+        NearToken::from_yoctonear(cost.as_u128())
+    }
+}
+
+fn mul_div(a: U256, b: U256, c: U256, round_up: bool) -> U256 {
+    let product = a.checked_mul(b).expect("Math overflow");
+    if round_up {
+        (product + c - 1) / c
+    } else {
+        product / c
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_linear_cost() {
+        // 1 NEAR per token for the first token, 3 NEAR per token for the last one
+        let curve = BondingCurve {
+            start_buy_amount: NearToken::from_near(10),
+            shape: CurveShape::Linear {
+                end_buy_amount: NearToken::from_near(30),
+            },
+        };
+
+        assert_eq!(curve.cost(U128(10), 0, 10, true), NearToken::from_near(20));
+        assert_eq!(
+            curve.cost(U128(10), 0, 5, true),
+            NearToken::from_millinear(7_500)
+        );
+        assert_eq!(
+            curve.cost(U128(10), 5, 10, false),
+            NearToken::from_millinear(12_500)
+        );
+    }
+
+    #[test]
+    fn test_exponential_cost() {
+        // 1 NEAR per token, doubling every 5 tokens
+        let curve = BondingCurve {
+            start_buy_amount: NearToken::from_near(10),
+            shape: CurveShape::Exponential {
+                doubling_amount: U128(5),
+            },
+        };
+
+        // 1 to 2 NEAR over the first 5 tokens, 2 to 4 NEAR over the next 5
+        assert_eq!(
+            curve.cost(U128(10), 0, 5, true),
+            NearToken::from_millinear(7_500)
+        );
+        assert_eq!(curve.cost(U128(10), 5, 10, true), NearToken::from_near(15));
+        assert_eq!(
+            curve.cost(U128(10), 0, 10, false),
+            NearToken::from_millinear(22_500)
+        );
+    }
+}
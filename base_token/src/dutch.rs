@@ -0,0 +1,131 @@
+use crate::price::mul_div;
+use crate::{BondingCurve, Price};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{near, NearToken};
+
+/// How the tokens of the auction are sold.
+#[near(serializers = [json, borsh])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuctionMode {
+    Batch, // uniform price auction, the order book is settled once bidding closes
+    Dutch(DutchAuction),
+    BondingCurve(BondingCurve), // tokens are bought and sold back at any time, there is no settlement
+}
+
+/// Descending price auction: every purchase fills at once at the current price, and every buyer
+/// pays the price of the last purchase in the end.
+#[near(serializers = [json, borsh])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DutchAuction {
+    pub start_buy_amount: NearToken, // near amount to pay for all tokens when bidding opens
+    pub curve: PriceCurve,
+}
+
+#[near(serializers = [json, borsh])]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PriceCurve {
+    Linear,                         // reaches the reserve price at the end of the auction
+    Exponential { half_life: U64 }, // the gap to the reserve price halves every half_life nanoseconds
+}
+
+impl DutchAuction {
+    /// Price at `elapsed` nanoseconds of an auction that lasts `duration`, falling from the start
+    /// price to the reserve price `reserve_buy_amount / supply`.
+    pub fn price_at(
+        &self,
+        reserve_buy_amount: NearToken,
+        supply: U128,
+        elapsed: u64,
+        duration: u64,
+    ) -> Price {
+        let elapsed = elapsed.min(duration);
+        let spread = self
+            .start_buy_amount
+            .saturating_sub(reserve_buy_amount)
+            .as_yoctonear();
+
+        let remaining_spread = match self.curve {
+            PriceCurve::Linear => spread - mul_div(spread, elapsed.into(), duration.into()),
+            // Halves for every whole half life, then falls linearly within the current one
+            PriceCurve::Exponential { half_life } => {
+                let halvings = elapsed / half_life.0;
+                let spread = spread.checked_shr(halvings as u32).unwrap_or(0);
+                spread
+                    - mul_div(
+                        spread / 2,
+                        (elapsed % half_life.0).into(),
+                        half_life.0.into(),
+                    )
+            }
+        };
+
+        Price::new(
+            reserve_buy_amount.saturating_add(NearToken::from_yoctonear(remaining_spread)),
+            supply,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_linear_price() {
+        let dutch = DutchAuction {
+            start_buy_amount: NearToken::from_near(100),
+            curve: PriceCurve::Linear,
+        };
+        let reserve = NearToken::from_near(20);
+
+        assert_eq!(
+            dutch
+                .price_at(reserve, U128(10), 0, 1_000)
+                .cost_of(U128(10)),
+            NearToken::from_near(100)
+        );
+        assert_eq!(
+            dutch
+                .price_at(reserve, U128(10), 250, 1_000)
+                .cost_of(U128(10)),
+            NearToken::from_near(80)
+        );
+        assert_eq!(
+            dutch
+                .price_at(reserve, U128(10), 2_000, 1_000)
+                .cost_of(U128(10)),
+            NearToken::from_near(20)
+        );
+    }
+
+    #[test]
+    fn test_exponential_price() {
+        let dutch = DutchAuction {
+            start_buy_amount: NearToken::from_near(100),
+            curve: PriceCurve::Exponential {
+                half_life: U64(100),
+            },
+        };
+        let reserve = NearToken::from_near(20);
+
+        // 80 NEAR above the reserve, then 40, then 20, and 30 half way through the second half life
+        assert_eq!(
+            dutch
+                .price_at(reserve, U128(10), 100, 1_000)
+                .cost_of(U128(10)),
+            NearToken::from_near(60)
+        );
+        assert_eq!(
+            dutch
+                .price_at(reserve, U128(10), 150, 1_000)
+                .cost_of(U128(10)),
+            NearToken::from_near(50)
+        );
+        assert_eq!(
+            dutch
+                .price_at(reserve, U128(10), 200, 1_000)
+                .cost_of(U128(10)),
+            NearToken::from_near(40)
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use crate::Price;
+use near_sdk::json_types::{U128, U64};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{serde_json, AccountId, NearToken};
+use std::fmt;
+
+pub const AUCTION_STANDARD_NAME: &str = "near_pump_auction";
+pub const AUCTION_STANDARD_VERSION: &str = "1.1.0";
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+#[serde(crate = "near_sdk::serde")]
+#[non_exhaustive]
+pub enum EventLogVariant {
+    BidderRegistered(Vec<BidderRegisteredLog>),
+    OrderPlaced(Vec<OrderPlacedLog>),
+    OrderCancelled(Vec<OrderCancelledLog>),
+    AuctionSettled(Vec<AuctionSettledLog>),
+    TokensClaimed(Vec<TokensClaimedLog>),
+    DepositRefunded(Vec<DepositRefundedLog>),
+    ProtocolFeePaid(Vec<ProtocolFeePaidLog>),
+    TokensBought(Vec<TradeLog>),
+    TokensSold(Vec<TradeLog>),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EventLog {
+    pub standard: String,
+    pub version: String,
+
+    #[serde(flatten)]
+    pub event: EventLogVariant,
+}
+
+impl EventLog {
+    pub fn new(event: EventLogVariant) -> Self {
+        Self {
+            standard: AUCTION_STANDARD_NAME.to_string(),
+            version: AUCTION_STANDARD_VERSION.to_string(),
+            event,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BidderRegisteredLog {
+    pub account_id: AccountId,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OrderPlacedLog {
+    pub order_id: U64,
+    pub bidder: AccountId,
+    pub buy_amount: U128,
+    pub sell_amount: NearToken,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OrderCancelledLog {
+    pub order_id: U64,
+    pub bidder: AccountId,
+    pub buy_amount: U128,
+    pub sell_amount: NearToken,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AuctionSettledLog {
+    pub is_failed: bool,
+    pub clearing_price: Option<Price>, // None when no order won
+    pub final_auction_price: NearToken,
+    pub filled_amount: U128, // tokens sold to the winning orders
+    pub unsold_amount: U128,
+    pub total_proceeds: NearToken,
+    pub winning_orders: U64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokensClaimedLog {
+    pub account_id: AccountId,
+    pub order_ids: Vec<U64>,
+    pub token_amount: U128,
+    pub refund: NearToken, // paid above the final price and returned with the tokens
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DepositRefundedLog {
+    pub account_id: AccountId,
+    pub order_ids: Vec<U64>,
+    pub amount: NearToken,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProtocolFeePaidLog {
+    pub recipient: AccountId,
+    pub fee_bps: u16,
+    pub amount: NearToken,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TradeLog {
+    pub account_id: AccountId,
+    pub token_amount: U128,
+    pub near_amount: NearToken,
+    pub curve_sold: U128, // tokens sold on the bonding curve after the trade
+}
+
+impl fmt::Display for EventLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!(
+            "EVENT_JSON:{}",
+            serde_json::to_string(self).map_err(|_| fmt::Error)?
+        ))
+    }
+}
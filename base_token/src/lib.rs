@@ -1,6 +1,8 @@
+use near_contract_standards::fungible_token::core::ext_ft_core;
 use near_contract_standards::fungible_token::metadata::{
     FungibleTokenMetadata, FungibleTokenMetadataProvider,
 };
+use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
 use near_contract_standards::fungible_token::{
     FungibleToken, FungibleTokenCore, FungibleTokenResolver,
 };
@@ -8,13 +10,39 @@ use near_contract_standards::storage_management::{
     StorageBalance, StorageBalanceBounds, StorageManagement,
 };
 use near_sdk::borsh::BorshSerialize;
-use near_sdk::collections::{LazyOption, Vector};
-use near_sdk::json_types::{U128, U64};
+use near_sdk::collections::{LazyOption, LookupMap, TreeMap, Vector};
+use near_sdk::json_types::{Base64VecU8, U128, U64};
 use near_sdk::{
-    env, log, near, require, AccountId, BorshStorageKey, NearToken, PanicOnDefault, Promise,
-    PromiseOrValue,
+    env, log, near, require, serde_json, AccountId, BorshStorageKey, Gas, NearToken,
+    PanicOnDefault, Promise, PromiseError, PromiseOrValue, StorageUsage,
 };
 
+mod allowlist;
+mod curve;
+mod dutch;
+mod events;
+mod price;
+mod sealed;
+mod settlement;
+mod vesting;
+pub use allowlist::{allowlist_leaf, allowlist_node, AllowlistEntry};
+use curve::MAX_DOUBLINGS;
+pub use curve::{BondingCurve, CurveShape};
+pub use dutch::{AuctionMode, DutchAuction, PriceCurve};
+pub use events::*;
+use price::mul_div;
+pub use price::Price;
+pub use sealed::{hash_order, Commitment, SealedBid};
+pub use settlement::{AuctionStatus, BookKey, Distribution, Settlement, SettlementPhase};
+pub use vesting::{CreatorAllocation, Vesting};
+
+const MAX_FEE_BPS: u16 = 10_000;
+const GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(10);
+const GAS_FOR_RESOLVE_QUOTE_TRANSFER: Gas = Gas::from_tgas(5);
+// Bytes an order takes in orders, order_positions, sorted_orders and bidder_orders, with room for
+// the longest account id and for the nodes the order book rebalances
+const ORDER_STORAGE_USAGE: StorageUsage = 700;
+
 #[derive(PanicOnDefault)]
 #[near(contract_state)]
 pub struct Contract {
@@ -23,36 +51,229 @@ pub struct Contract {
     image: String,
     description: String,
     auction: Auction,
-    orders: Vector<Order>,
+    orders: Vector<Order>, // every order in placement order, cancelled orders stay as tombstones
+    order_positions: LookupMap<u64, u64>, // position of every open order id in orders
+    bidder_orders: LookupMap<AccountId, Vec<u64>>, // ids of the orders placed by every bidder
+    next_order_id: u64,
+    settlement: Settlement,
+    sorted_orders: TreeMap<BookKey, u64>, // ids of the open orders kept sorted by price as orders are placed
+    winning_orders: Vector<(Order, bool)>,
+    winning_positions: LookupMap<u64, u64>, // position of every winning order id in winning_orders
+    refunded_orders: Vector<Order>,
+    refunded_positions: LookupMap<u64, u64>, // position of every refunded order id in refunded_orders
+    distribution: Distribution,
+    unpaid_transfers: LookupMap<AccountId, NearToken>, // quote token transfers that failed, kept for the receiver
+    commitments: Vector<Commitment>, // sealed bids, the id of a commitment is its position
+    vestings: LookupMap<AccountId, Vesting>, // tokens held by the contract until they vest
+    allowlist: LookupMap<AccountId, AllowlistEntry>, // accounts that proved they are on the allowlist
+    account_fills: LookupMap<AccountId, U128>, // tokens bought from the curve by every account with a limit
 }
 
 #[near(serializers = [json, borsh])]
 #[derive(Clone)]
 pub struct Auction {
     auctioner: AccountId,
-    auction_duration: U64,
+    auction_start: U64,          // bids are accepted from this timestamp
+    auction_end: U64, // bids are accepted until this timestamp, pushed out by the soft close
     auctioned_sell_amount: U128, //total amount of tokens to sell in the auction
     min_buy_amount: NearToken, // near amount to pay for all tokens
     is_settled: bool,
-    winning_orders: Vec<(Order, bool)>,
+    is_failed: bool, // the auction settled without reaching min_raise, every order is refunded
     final_auction_price: NearToken, // price for the last token bought that applies for all the tokens bought
-    refunded_orders: Vec<Order>,
+    clearing_price: Option<Price>, // exact price of the last winning order, used to compute refunds
+    cancellation_cutoff: Option<U64>, // orders can not be cancelled during this last period of the auction
+    min_raise: Option<NearToken>, // the auction fails if the open orders deposit less than this amount
+    total_bids: NearToken,        // near deposited by all the open orders
+    unsold_supply: UnsoldSupply,
+    unsold_amount: U128,       // tokens left unsold once the auction is settled
+    total_proceeds: NearToken, // near paid for the tokens sold, known once the auction is settled
+    withdrawn_proceeds: NearToken, // proceeds already sent to the auctioner
+    protocol_fee: Option<ProtocolFee>,
+    fee_amount: NearToken, // part of the proceeds paid as protocol fee at settlement
+    quote_token: Option<AccountId>, // NEP-141 token the bids are placed in, NEAR if not set
+    sealed_bid: Option<SealedBid>,
+    reveal_end: U64, // sealed bids are revealed until this timestamp, equal to auction_end without sealed bids
+    mode: AuctionMode,
+    curve_sold: U128,         // tokens sold on the bonding curve and not sold back
+    curve_reserve: NearToken, // near paid for the tokens sold on the bonding curve
+    allowlist_root: Option<Base64VecU8>, // merkle root of the accounts allowed to bid, anyone can bid if not set
+    max_buy_per_account: Option<U128>,   // most tokens a bidder can buy across all its orders
+    soft_close: Option<SoftClose>,
+    scheduled_end: U64,    // end of the auction before any soft close extension
+    status: AuctionStatus, // last status stored, get_auction_status also follows the clock
+    status_changed_at: U64,
+}
+
+// Timestamps in nanoseconds. The end of the auction is given either as a timestamp or as a duration from the start
+#[near(serializers = [json])]
+pub struct AuctionSchedule {
+    pub start: Option<U64>, // bidding opens as soon as the contract is created if not set
+    pub end: Option<U64>,
+    pub duration: Option<U64>,
+}
+
+// Auction chosen when the contract is created, min_buy_amount is the least the auctioner accepts for the whole supply
+#[near(serializers = [json])]
+pub struct AuctionConfig {
+    pub schedule: AuctionSchedule,
+    pub min_buy_amount: NearToken,
+    pub options: Option<AuctionOptions>,
+}
+
+// Optional settings of the auction, chosen when the contract is created
+#[near(serializers = [json])]
+#[derive(Default)]
+pub struct AuctionOptions {
+    pub min_raise: Option<NearToken>,
+    pub unsold_supply: Option<UnsoldSupply>, // tokens are returned to the auctioner if not set
+    pub protocol_fee: Option<ProtocolFee>,   // set by the factory that creates the token
+    // Bids are placed in this NEP-141 token with ft_transfer_call instead of NEAR. Every amount
+    // of the auction (min_buy_amount, min_raise, the orders and the proceeds) is then in its units
+    pub quote_token: Option<AccountId>,
+    pub sealed_bid: Option<SealedBid>, // bids are committed as hashes and revealed once bidding closes
+    pub mode: Option<AuctionMode>,     // batch auction if not set
+    pub creator_allocation: Option<CreatorAllocation>, // minted on top of the auctioned supply
+    pub allowlist_root: Option<Base64VecU8>, // only the accounts of the allowlist can bid if set
+    pub max_buy_per_account: Option<U128>,
+    pub soft_close: Option<SoftClose>, // bids at the end of the auction push its end out
+}
+
+// Message of the ft_transfer_call that places a bid in the quote token
+#[near(serializers = [json])]
+pub struct BidMessage {
+    pub buy_amount: U128,
+}
+
+// Fee taken from the proceeds of the auction at settlement
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct ProtocolFee {
+    pub fee_bps: u16,
+    pub recipient: AccountId,
+}
+
+// Anti-sniping rule, timestamps in nanoseconds. An order placed in the last window of the auction
+// pushes its end out by extension, the end is never pushed more than max_extension past the scheduled end
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct SoftClose {
+    pub window: U64,
+    pub extension: U64,
+    pub max_extension: U64,
+}
+
+// What happens at settlement to the tokens that are not sold, either because the winning orders
+// do not cover the whole supply or because the auction failed
+#[near(serializers = [json, borsh])]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnsoldSupply {
+    ReturnToAuctioner,
+    Burn,           // an FtBurn event is emitted
+    KeepInTreasury, // tokens stay in the account of the contract
 }
 
 #[near(serializers = [json, borsh])]
 #[derive(Clone)]
 pub struct Order {
+    id: U64, // stable identifier of the order, assigned when the order is placed
     bidder: AccountId,
     buy_amount: U128,       // amount of tokens to buy
     sell_amount: NearToken, // near amount to pay for the buy_amount
 }
 
+// Auction info without the order lists, cheap enough to be polled by clients
+#[near(serializers = [json])]
+pub struct AuctionSummary {
+    auctioner: AccountId,
+    auction_start: U64,
+    auction_end: U64,
+    auctioned_sell_amount: U128,
+    min_buy_amount: NearToken,
+    is_settled: bool,
+    is_failed: bool,
+    final_auction_price: NearToken,
+    clearing_price: Option<Price>,
+    cancellation_cutoff: Option<U64>,
+    min_raise: Option<NearToken>,
+    total_bids: NearToken,
+    unsold_supply: UnsoldSupply,
+    unsold_amount: U128,
+    total_proceeds: NearToken,
+    withdrawn_proceeds: NearToken,
+    protocol_fee: Option<ProtocolFee>,
+    fee_amount: NearToken,
+    quote_token: Option<AccountId>,
+    sealed_bid: Option<SealedBid>,
+    reveal_end: U64,
+    mode: AuctionMode,
+    curve_sold: U128,
+    curve_reserve: NearToken,
+    allowlist_root: Option<Base64VecU8>,
+    max_buy_per_account: Option<U128>,
+    soft_close: Option<SoftClose>,
+    scheduled_end: U64,
+    status: AuctionStatus,
+    total_orders: U64,
+    total_winning_orders: U64,
+    total_refunded_orders: U64,
+}
+
+#[near(serializers = [json])]
+pub struct AuctionCountdown {
+    auction_start: U64,
+    auction_end: U64,
+    starts_in: U64, // nanoseconds until bidding opens, 0 once it is open
+    ends_in: U64,   // nanoseconds until bidding closes, 0 once the auction has ended
+}
+
+#[near(serializers = [json])]
+pub struct AuctionStatusView {
+    status: AuctionStatus,
+    since: U64, // timestamp the auction entered the status
+    auction_start: U64,
+    auction_end: U64,
+    reveal_end: U64,
+}
+
+impl AuctionSchedule {
+    // Returns the start and end timestamps of the auction
+    fn resolve(&self) -> (U64, U64) {
+        let start = self.start.map_or(env::block_timestamp(), |start| start.0);
+        let end = match (self.end, self.duration) {
+            (Some(end), None) => end.0,
+            (None, Some(duration)) => start.checked_add(duration.0).expect("Math overflow"),
+            _ => env::panic_str("Either the auction end or the auction duration must be set"),
+        };
+        assert!(end > start, "Auction end must be after the auction start");
+
+        (U64(start), U64(end))
+    }
+}
+
+impl Order {
+    fn price(&self) -> Price {
+        Price::new(self.sell_amount, self.buy_amount)
+    }
+}
+
 #[derive(BorshSerialize, BorshStorageKey)]
 #[borsh(crate = "near_sdk::borsh")]
 enum StorageKey {
     FungibleToken,
     Metadata,
     Orders,
+    OrderPositions,
+    BidderOrders,
+    SortedOrders,
+    WinningOrders,
+    WinningPositions,
+    RefundedOrders,
+    RefundedPositions,
+    UnpaidTransfers,
+    Commitments,
+    Vestings,
+    Allowlist,
+    AccountFills,
 }
 
 #[near]
@@ -65,11 +286,92 @@ impl Contract {
         metadata: FungibleTokenMetadata,
         image: String,
         description: String,
-        auction_duration: U64,
-        min_buy_amount: NearToken,
+        auction: AuctionConfig,
     ) -> Self {
         require!(!env::state_exists(), "Already initialized");
         metadata.assert_valid();
+        let AuctionConfig {
+            schedule,
+            min_buy_amount,
+            options,
+        } = auction;
+        let (auction_start, auction_end) = schedule.resolve();
+        let options = options.unwrap_or_default();
+        if let Some(protocol_fee) = &options.protocol_fee {
+            assert!(
+                protocol_fee.fee_bps <= MAX_FEE_BPS,
+                "Protocol fee can not be more than {} basis points",
+                MAX_FEE_BPS
+            );
+        }
+        let reveal_end = match &options.sealed_bid {
+            Some(sealed_bid) => {
+                assert!(
+                    sealed_bid.penalty_bps <= MAX_FEE_BPS,
+                    "Penalty can not be more than {} basis points",
+                    MAX_FEE_BPS
+                );
+                assert!(
+                    options.quote_token.is_none(),
+                    "Sealed bids can not be placed in a quote token"
+                );
+                U64(auction_end
+                    .0
+                    .checked_add(sealed_bid.reveal_duration.0)
+                    .expect("Math overflow"))
+            }
+            None => auction_end,
+        };
+        if let Some(root) = &options.allowlist_root {
+            allowlist::assert_valid_root(root);
+        }
+        if let Some(max_buy_per_account) = options.max_buy_per_account {
+            assert!(
+                max_buy_per_account.0 > 0,
+                "Max buy per account must be greater than 0"
+            );
+        }
+        let mode = options.mode.unwrap_or(AuctionMode::Batch);
+        if let Some(soft_close) = &options.soft_close {
+            assert!(
+                soft_close.window.0 > 0 && soft_close.extension.0 > 0,
+                "Soft close window and extension must be greater than 0"
+            );
+            assert!(
+                mode == AuctionMode::Batch,
+                "Only a batch auction can have a soft close"
+            );
+        }
+        if let AuctionMode::Dutch(dutch) = &mode {
+            assert!(
+                dutch.start_buy_amount >= min_buy_amount,
+                "The start price of a Dutch auction can not be below the reserve price"
+            );
+            assert!(
+                options.sealed_bid.is_none(),
+                "A Dutch auction can not take sealed bids"
+            );
+            if let PriceCurve::Exponential { half_life } = dutch.curve {
+                assert!(half_life.0 > 0, "Half life must be greater than 0");
+            }
+        }
+        if let AuctionMode::BondingCurve(curve) = &mode {
+            match curve.shape {
+                CurveShape::Linear { end_buy_amount } => assert!(
+                    end_buy_amount >= curve.start_buy_amount,
+                    "The end price of a bonding curve can not be below its start price"
+                ),
+                CurveShape::Exponential { doubling_amount } => assert!(
+                    doubling_amount.0 > 0 && total_supply.0 / doubling_amount.0 <= MAX_DOUBLINGS,
+                    "The price of a bonding curve can double at most {} times",
+                    MAX_DOUBLINGS
+                ),
+            }
+            assert!(
+                options.sealed_bid.is_none() && options.quote_token.is_none(),
+                "A bonding curve is traded in NEAR without sealed bids"
+            );
+        }
 
         let mut this = Self {
             token: FungibleToken::new(StorageKey::FungibleToken),
@@ -78,18 +380,57 @@ impl Contract {
             description,
             auction: Auction {
                 auctioner: owner_id,
-                auction_duration,
+                auction_start,
+                auction_end,
                 auctioned_sell_amount: total_supply,
                 min_buy_amount,
                 is_settled: false,
-                winning_orders: Vec::new(),
+                is_failed: false,
                 final_auction_price: NearToken::from_yoctonear(0),
-                refunded_orders: Vec::new(),
+                clearing_price: None,
+                cancellation_cutoff: None,
+                min_raise: options.min_raise,
+                total_bids: NearToken::from_yoctonear(0),
+                unsold_supply: options
+                    .unsold_supply
+                    .unwrap_or(UnsoldSupply::ReturnToAuctioner),
+                unsold_amount: U128(0),
+                total_proceeds: NearToken::from_yoctonear(0),
+                withdrawn_proceeds: NearToken::from_yoctonear(0),
+                protocol_fee: options.protocol_fee,
+                fee_amount: NearToken::from_yoctonear(0),
+                quote_token: options.quote_token,
+                sealed_bid: options.sealed_bid,
+                reveal_end,
+                mode,
+                curve_sold: U128(0),
+                curve_reserve: NearToken::from_yoctonear(0),
+                allowlist_root: options.allowlist_root,
+                max_buy_per_account: options.max_buy_per_account,
+                soft_close: options.soft_close,
+                scheduled_end: auction_end,
+                status: AuctionStatus::Scheduled,
+                status_changed_at: U64(env::block_timestamp()),
             },
             orders: Vector::new(StorageKey::Orders),
+            order_positions: LookupMap::new(StorageKey::OrderPositions),
+            bidder_orders: LookupMap::new(StorageKey::BidderOrders),
+            next_order_id: 0,
+            settlement: Settlement::default(),
+            sorted_orders: TreeMap::new(StorageKey::SortedOrders),
+            winning_orders: Vector::new(StorageKey::WinningOrders),
+            winning_positions: LookupMap::new(StorageKey::WinningPositions),
+            refunded_orders: Vector::new(StorageKey::RefundedOrders),
+            refunded_positions: LookupMap::new(StorageKey::RefundedPositions),
+            distribution: Distribution::default(),
+            unpaid_transfers: LookupMap::new(StorageKey::UnpaidTransfers),
+            commitments: Vector::new(StorageKey::Commitments),
+            vestings: LookupMap::new(StorageKey::Vestings),
+            allowlist: LookupMap::new(StorageKey::Allowlist),
+            account_fills: LookupMap::new(StorageKey::AccountFills),
         };
 
-        // Assign the tokens to the contract itself 
+        // Assign the tokens to the contract itself
         this.token
             .internal_register_account(&env::current_account_id());
         this.token
@@ -102,9 +443,80 @@ impl Contract {
         }
         .emit();
 
+        // The allocation stays in the account of the contract, so it can not be transferred until it vests
+        if let Some(allocation) = options.creator_allocation {
+            this.add_creator_allocation(allocation);
+        }
+
         this
     }
 
+    fn add_creator_allocation(&mut self, allocation: CreatorAllocation) {
+        let creator = self.auction.auctioner.clone();
+        assert!(
+            creator != env::current_account_id(),
+            "The creator allocation can not be vested to the contract itself"
+        );
+        assert!(
+            allocation.cliff <= allocation.duration,
+            "The cliff can not be longer than the vesting"
+        );
+
+        let start = self.auction.auction_end.0;
+        let vesting = Vesting {
+            total: allocation.amount,
+            claimed: U128(0),
+            start: U64(start),
+            cliff_end: U64(start
+                .checked_add(allocation.cliff.0)
+                .expect("Math overflow")),
+            end: U64(start
+                .checked_add(allocation.duration.0)
+                .expect("Math overflow")),
+        };
+        self.vestings.insert(&creator, &vesting);
+
+        self.token
+            .internal_deposit(&env::current_account_id(), allocation.amount.0);
+        near_contract_standards::fungible_token::events::FtMint {
+            owner_id: &env::current_account_id(),
+            amount: allocation.amount,
+            memo: Some("Creator allocation is minted and vesting"),
+        }
+        .emit();
+    }
+
+    // Sends the caller the tokens vested so far that were not claimed yet
+    pub fn claim_vested(&mut self) -> U128 {
+        let account_id = env::predecessor_account_id();
+        let mut vesting = self
+            .vestings
+            .get(&account_id)
+            .expect("No vesting found for this account");
+
+        let claimable = vesting.vested(env::block_timestamp()).0 - vesting.claimed.0;
+        assert!(claimable > 0, "No vested tokens to claim");
+
+        vesting.claimed = U128(vesting.claimed.0 + claimable);
+        self.vestings.insert(&account_id, &vesting);
+
+        if !self.token.accounts.contains_key(&account_id) {
+            self.token.internal_register_account(&account_id);
+        }
+        self.token.internal_transfer(
+            &env::current_account_id(),
+            &account_id,
+            claimable,
+            Some("Vested tokens".to_string()),
+        );
+
+        U128(claimable)
+    }
+
+    pub fn get_vesting(&self, account_id: AccountId) -> Option<Vesting> {
+        self.vestings.get(&account_id)
+    }
+
     #[payable]
     pub fn register_bidder(&mut self) {
         let account_id = env::predecessor_account_id();
@@ -128,25 +540,526 @@ impl Contract {
             Promise::new(account_id.clone()).transfer(refund);
         }
 
-        log!("Account @{} registered as a bidder", account_id);
+        let bidder_registered_log = EventLog::new(EventLogVariant::BidderRegistered(vec![
+            BidderRegisteredLog { account_id },
+        ]));
+        log!("{}", bidder_registered_log);
+    }
+
+    // Proves that the caller is on the allowlist, required before bidding when the auction has one.
+    // The cap is the one encoded in the leaf of the caller, None if the leaf has no cap
+    pub fn join_allowlist(&mut self, proof: Vec<Base64VecU8>, allocation_cap: Option<U128>) {
+        let account_id = env::predecessor_account_id();
+        let root = self
+            .auction
+            .allowlist_root
+            .clone()
+            .expect("The auction has no allowlist");
+        self.assert_status(
+            &[AuctionStatus::Scheduled, AuctionStatus::Open],
+            "The allowlist can only be joined until bidding closes",
+        );
+
+        assert!(
+            allowlist::verify_proof(
+                &root.0,
+                allowlist::allowlist_leaf(&account_id, allocation_cap),
+                &proof
+            ),
+            "Invalid allowlist proof"
+        );
+
+        let entry = AllowlistEntry {
+            root,
+            cap: allocation_cap,
+        };
+        self.allowlist.insert(&account_id, &entry);
+
+        log!("Account @{} joined the allowlist", account_id);
+    }
+
+    // Replaces the allowlist, proofs of the previous root are no longer accepted
+    pub fn set_allowlist_root(&mut self, allowlist_root: Option<Base64VecU8>) {
+        assert!(
+            env::predecessor_account_id() == self.auction.auctioner,
+            "Only the auctioner can set the allowlist"
+        );
+        self.assert_status(
+            &[AuctionStatus::Scheduled],
+            "The allowlist can not be changed once bidding opens",
+        );
+        if let Some(root) = &allowlist_root {
+            allowlist::assert_valid_root(root);
+        }
+
+        self.auction.allowlist_root = allowlist_root;
     }
 
+    pub fn get_allowlist_entry(&self, account_id: AccountId) -> Option<AllowlistEntry> {
+        self.allowlist.get(&account_id)
+    }
+
+    // Returns true once the order is placed, its id is the last one in get_orders.
+    // A bidder that is not registered yet is registered here. The storage of the registration and of the
+    // order is taken from the attached deposit, the rest of it is the sell amount of the order
     #[payable]
     pub fn place_order(&mut self, buy_amount: U128) -> bool {
-        let sell_amount = env::attached_deposit();
+        if let Some(quote_token) = &self.auction.quote_token {
+            env::panic_str(&format!(
+                "Bids are placed in {} with ft_transfer_call",
+                quote_token
+            ));
+        }
+        assert!(
+            self.auction.sealed_bid.is_none(),
+            "Bids are committed with commit_order in a sealed-bid auction"
+        );
+
+        let bidder = env::predecessor_account_id();
+        let deposit = self.register_from_deposit(&bidder, env::attached_deposit());
+        let sell_amount = self.charge_order_storage(deposit);
+
+        self.assert_bidding_open();
+        self.internal_place_order(bidder, buy_amount, sell_amount);
+
+        true
+    }
+
+    // Registers the account when needed and returns the part of the deposit left once the storage is paid
+    fn register_from_deposit(&mut self, account_id: &AccountId, deposit: NearToken) -> NearToken {
+        if self.token.accounts.contains_key(account_id) {
+            return deposit;
+        }
 
+        let storage_cost = self.storage_balance_bounds().min;
         assert!(
-            self.token
-                .accounts
-                .contains_key(&env::predecessor_account_id()),
-            "Account is not registered in token"
+            deposit > storage_cost,
+            "The attached deposit must cover the storage cost of {} and the order",
+            storage_cost
+        );
+
+        self.token.internal_register_account(account_id);
+        let bidder_registered_log = EventLog::new(EventLogVariant::BidderRegistered(vec![
+            BidderRegisteredLog {
+                account_id: account_id.clone(),
+            },
+        ]));
+        log!("{}", bidder_registered_log);
+
+        deposit.saturating_sub(storage_cost)
+    }
+
+    // The storage of an order is kept by the contract, it is not refunded with the deposit
+    fn charge_order_storage(&self, deposit: NearToken) -> NearToken {
+        let storage_cost = self.get_order_storage_cost();
+        assert!(
+            deposit > storage_cost,
+            "The attached deposit must cover the order storage cost of {}",
+            storage_cost
+        );
+        deposit.saturating_sub(storage_cost)
+    }
+
+    pub fn get_order_storage_cost(&self) -> NearToken {
+        env::storage_byte_cost().saturating_mul(ORDER_STORAGE_USAGE.into())
+    }
+
+    fn assert_bidding_open(&mut self) {
+        let message = match self.current_status() {
+            AuctionStatus::Scheduled => "Auction has not started yet",
+            AuctionStatus::Cancelled => "Auction is cancelled",
+            _ => "Auction has ended",
+        };
+        self.assert_status(&[AuctionStatus::Open], message);
+    }
+
+    // Status of the auction at the current block, the phases that follow the clock are derived from the timestamps
+    fn current_status(&self) -> AuctionStatus {
+        match self.auction.status {
+            AuctionStatus::Scheduled | AuctionStatus::Open | AuctionStatus::Ended => {
+                let now = env::block_timestamp();
+                if now < self.auction.auction_start.0 {
+                    AuctionStatus::Scheduled
+                } else if now < self.auction.auction_end.0
+                    || matches!(self.auction.mode, AuctionMode::BondingCurve(_))
+                {
+                    AuctionStatus::Open
+                } else {
+                    AuctionStatus::Ended
+                }
+            }
+            status => status,
+        }
+    }
+
+    fn set_status(&mut self, status: AuctionStatus) {
+        if self.auction.status != status {
+            self.auction.status = status;
+            self.auction.status_changed_at = U64(env::block_timestamp());
+        }
+    }
+
+    // Every method that depends on the phase of the auction goes through this guard, it stores the
+    // current status and panics with message if it is not one of the allowed ones
+    fn assert_status(&mut self, allowed: &[AuctionStatus], message: &str) -> AuctionStatus {
+        let status = self.current_status();
+        self.set_status(status);
+        assert!(allowed.contains(&status), "{}", message);
+        status
+    }
+
+    // Panics if the auction has an allowlist the account did not join. Returns the allocation cap of the account
+    fn assert_allowlisted(&self, account_id: &AccountId) -> Option<U128> {
+        let root = self.auction.allowlist_root.as_ref()?;
+        let entry = self
+            .allowlist
+            .get(account_id)
+            .filter(|entry| &entry.root == root)
+            .expect("Account is not on the allowlist, join it with a proof first");
+        entry.cap
+    }
+
+    // Tokens asked by all the orders of the bidder
+    fn bidder_buy_amount(&self, bidder: &AccountId) -> u128 {
+        self.bidder_orders
+            .get(bidder)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|order_id| self.find_order(U64(order_id)))
+            .map(|(_, order)| order.buy_amount.0)
+            .sum()
+    }
+
+    fn assert_within_allocation_cap(&self, cap: Option<U128>, buy_amount: u128) {
+        if let Some(cap) = cap {
+            assert!(
+                buy_amount <= cap.0,
+                "Buy amount is above the allocation cap of {}",
+                cap.0
+            );
+        }
+    }
+
+    // The orders of a bidder together can not ask for more than the limit per account, so the
+    // settlement never fills more than the limit. buy_amount is the total of the bidder
+    fn assert_within_max_buy(&self, buy_amount: u128) {
+        if let Some(max_buy_per_account) = self.auction.max_buy_per_account {
+            assert!(
+                buy_amount <= max_buy_per_account.0,
+                "Buy amount is above the limit of {} tokens per account",
+                max_buy_per_account.0
+            );
+        }
+    }
+
+    fn internal_place_order(
+        &mut self,
+        bidder: AccountId,
+        buy_amount: U128,
+        sell_amount: NearToken,
+    ) {
+        assert!(
+            !matches!(self.auction.mode, AuctionMode::BondingCurve(_)),
+            "Tokens of a bonding curve are traded with buy and sell"
+        );
+        self.assert_valid_order(buy_amount, sell_amount);
+        let bidder_buy_amount = self.bidder_buy_amount(&bidder) + buy_amount.0;
+        let cap = self.assert_allowlisted(&bidder);
+        self.assert_within_allocation_cap(cap, bidder_buy_amount);
+        self.assert_within_max_buy(bidder_buy_amount);
+        let dutch_price = self.get_dutch_auction_price();
+        if let Some(price) = dutch_price {
+            let remaining_tokens =
+                self.auction.auctioned_sell_amount.0 - self.settlement.filled_amount.0;
+            assert!(remaining_tokens > 0, "Every token is already sold");
+            assert!(
+                buy_amount.0 <= remaining_tokens,
+                "Buy amount is greater than the {} tokens left",
+                remaining_tokens
+            );
+            assert!(
+                Price::new(sell_amount, buy_amount) >= price,
+                "Offer price is less than the current price of the Dutch auction"
+            );
+        }
+
+        let order = Order {
+            id: U64(self.next_order_id),
+            bidder,
+            buy_amount,
+            sell_amount,
+        };
+        self.next_order_id += 1;
+
+        self.order_positions.insert(&order.id.0, &self.orders.len());
+        self.orders.push(&order);
+        self.sorted_orders
+            .insert(&Self::book_key(&order), &order.id.0);
+        self.auction.total_bids = self.auction.total_bids.saturating_add(sell_amount);
+
+        let mut bidder_orders = self.bidder_orders.get(&order.bidder).unwrap_or_default();
+        bidder_orders.push(order.id.0);
+        self.bidder_orders.insert(&order.bidder, &bidder_orders);
+
+        let order_placed_log = EventLog::new(EventLogVariant::OrderPlaced(vec![OrderPlacedLog {
+            order_id: order.id,
+            bidder: order.bidder.clone(),
+            buy_amount: order.buy_amount,
+            sell_amount: order.sell_amount,
+        }]));
+        log!("{}", order_placed_log);
+
+        if let Some(price) = dutch_price {
+            self.fill_dutch_order(order, price);
+        } else {
+            self.extend_auction_end();
+        }
+    }
+
+    // Applies the soft close: a bid in the last window of the auction pushes its end out, and the
+    // reveal period of a sealed-bid auction with it. Revealed orders come after the end and never extend it
+    fn extend_auction_end(&mut self) {
+        let Some(soft_close) = &self.auction.soft_close else {
+            return;
+        };
+        let now = env::block_timestamp();
+        let auction_end = self.auction.auction_end.0;
+        if now >= auction_end || now < auction_end.saturating_sub(soft_close.window.0) {
+            return;
+        }
+
+        let max_end = self
+            .auction
+            .scheduled_end
+            .0
+            .saturating_add(soft_close.max_extension.0);
+        let new_end = auction_end
+            .saturating_add(soft_close.extension.0)
+            .min(max_end);
+        if new_end <= auction_end {
+            return;
+        }
+
+        self.auction.reveal_end = U64(self.auction.reveal_end.0 + (new_end - auction_end));
+        self.auction.auction_end = U64(new_end);
+        log!("Auction end extended to {}", new_end);
+    }
+
+    // A purchase of a Dutch auction wins at once. The price only falls, so the current price is the
+    // clearing price so far. The auction is settled as soon as every token is sold
+    fn fill_dutch_order(&mut self, order: Order, price: Price) {
+        self.settlement.filled_amount = Self::add(self.settlement.filled_amount, order.buy_amount);
+        self.settlement.clearing_price = Some(price);
+        self.push_winning_order(order);
+
+        if self.settlement.filled_amount == self.auction.auctioned_sell_amount {
+            log!("Every token is sold, the Dutch auction is settled");
+            self.settle(u64::MAX);
+        }
+    }
+
+    // Commits a sealed bid, the hash is built with hash_order and the attached deposit has to cover the
+    // sell amount of the hidden order. A bidder that is not registered yet is registered like in place_order.
+    // Returns the id of the commitment
+    #[payable]
+    pub fn commit_order(&mut self, hash: Base64VecU8) -> U64 {
+        let bidder = env::predecessor_account_id();
+
+        assert!(
+            self.auction.sealed_bid.is_some(),
+            "Commitments are only accepted in a sealed-bid auction"
+        );
+        let deposit = self.register_from_deposit(&bidder, env::attached_deposit());
+        self.assert_bidding_open();
+        self.assert_allowlisted(&bidder);
+        assert!(
+            deposit > NearToken::from_yoctonear(0),
+            "A deposit is required to commit an order"
+        );
+        assert!(hash.0.len() == 32, "The hash of the order must be 32 bytes");
+
+        let commitment = Commitment {
+            id: U64(self.commitments.len()),
+            bidder,
+            hash,
+            deposit,
+            revealed: false,
+            refunded: false,
+        };
+        self.commitments.push(&commitment);
+        self.extend_auction_end();
+
+        commitment.id
+    }
+
+    // Turns a commitment into an order once bidding has closed. The deposit above the sell amount is returned
+    pub fn reveal_order(
+        &mut self,
+        commitment_id: U64,
+        buy_amount: U128,
+        sell_amount: NearToken,
+        salt: Base64VecU8,
+    ) {
+        let message = "Orders can only be revealed during the reveal period";
+        self.assert_status(&[AuctionStatus::Ended], message);
+        assert!(
+            env::block_timestamp() < self.auction.reveal_end.0,
+            "{}",
+            message
         );
 
+        let mut commitment = self
+            .commitments
+            .get(commitment_id.0)
+            .expect("Commitment not found");
+        assert!(
+            commitment.bidder == env::predecessor_account_id(),
+            "Only the bidder can reveal the order"
+        );
+        assert!(!commitment.revealed, "Order already revealed");
+        assert!(
+            hash_order(&commitment.bidder, buy_amount, sell_amount, &salt.0) == commitment.hash.0,
+            "Revealed order does not match the commitment"
+        );
+        let available = commitment
+            .deposit
+            .saturating_sub(self.get_order_storage_cost());
         assert!(
-            self.auction.auction_duration.0 > env::block_timestamp(),
-            "Auction has ended"
+            sell_amount <= available,
+            "The deposit of the commitment does not cover the sell amount and the order storage"
         );
 
+        commitment.revealed = true;
+        self.commitments.replace(commitment_id.0, &commitment);
+        self.internal_place_order(commitment.bidder.clone(), buy_amount, sell_amount);
+
+        let refund = available.saturating_sub(sell_amount);
+        if refund > NearToken::from_yoctonear(0) {
+            Promise::new(commitment.bidder).transfer(refund);
+        }
+    }
+
+    // Returns the deposit of a commitment that was never revealed, minus the penalty that goes to the
+    // auctioner. Anyone can call it once the reveal period is over, the deposit always goes to the bidder
+    pub fn refund_commitment(&mut self, commitment_id: U64) {
+        let sealed_bid = self
+            .auction
+            .sealed_bid
+            .clone()
+            .expect("Commitments are only accepted in a sealed-bid auction");
+        let message = "Reveal period has not ended yet";
+        self.assert_status(
+            &[
+                AuctionStatus::Ended,
+                AuctionStatus::Settling,
+                AuctionStatus::Settled,
+                AuctionStatus::Failed,
+            ],
+            message,
+        );
+        assert!(
+            self.auction.reveal_end.0 <= env::block_timestamp(),
+            "{}",
+            message
+        );
+
+        let mut commitment = self
+            .commitments
+            .get(commitment_id.0)
+            .expect("Commitment not found");
+        assert!(
+            !commitment.revealed && !commitment.refunded,
+            "Commitment was revealed or already refunded"
+        );
+
+        commitment.refunded = true;
+        self.commitments.replace(commitment_id.0, &commitment);
+
+        let penalty = NearToken::from_yoctonear(mul_div(
+            commitment.deposit.as_yoctonear(),
+            sealed_bid.penalty_bps.into(),
+            MAX_FEE_BPS.into(),
+        ));
+        let refund = commitment.deposit.saturating_sub(penalty);
+
+        if penalty > NearToken::from_yoctonear(0) {
+            Promise::new(self.auction.auctioner.clone()).transfer(penalty);
+        }
+        if refund > NearToken::from_yoctonear(0) {
+            Promise::new(commitment.bidder).transfer(refund);
+        }
+    }
+
+    // Raises the price or changes the size of an open order, the order keeps its id and its position in get_orders.
+    // The attached deposit tops up the order and anything above the new sell amount is refunded.
+    // Orders in a quote token can not be topped up, only their size or their deposit can be lowered
+    #[payable]
+    pub fn amend_order(&mut self, order_id: U64, buy_amount: U128, sell_amount: NearToken) {
+        let bidder = env::predecessor_account_id();
+
+        assert!(
+            self.auction.mode == AuctionMode::Batch,
+            "Purchases of a Dutch auction are final"
+        );
+        self.assert_bidding_open();
+
+        let (position, order) = self.find_order(order_id).expect("Order not found");
+        assert!(
+            order.bidder == bidder,
+            "Only the bidder can amend the order"
+        );
+
+        self.assert_valid_order(buy_amount, sell_amount);
+        let bidder_buy_amount = self.bidder_buy_amount(&bidder) - order.buy_amount.0 + buy_amount.0;
+        self.assert_within_max_buy(bidder_buy_amount);
+        let cap = self.assert_allowlisted(&bidder);
+        self.assert_within_allocation_cap(cap, bidder_buy_amount);
+        assert!(
+            Price::new(sell_amount, buy_amount) >= order.price(),
+            "Amended price can not be lower than the current price"
+        );
+
+        // Lowering the deposit withdraws funds like a cancellation does
+        if sell_amount < order.sell_amount {
+            self.assert_cancellation_open();
+        }
+
+        let top_up = env::attached_deposit();
+        assert!(
+            self.auction.quote_token.is_none() || top_up == NearToken::from_yoctonear(0),
+            "Orders in the quote token can not be topped up with NEAR"
+        );
+
+        let available = order.sell_amount.saturating_add(top_up);
+        assert!(
+            available >= sell_amount,
+            "The attached deposit is too small. Required: {}",
+            sell_amount.saturating_sub(order.sell_amount)
+        );
+
+        let amended_order = Order {
+            id: order_id,
+            bidder: bidder.clone(),
+            buy_amount,
+            sell_amount,
+        };
+        self.orders.replace(position, &amended_order);
+        self.sorted_orders.remove(&Self::book_key(&order));
+        self.sorted_orders
+            .insert(&Self::book_key(&amended_order), &order_id.0);
+        self.auction.total_bids = self
+            .auction
+            .total_bids
+            .saturating_sub(order.sell_amount)
+            .saturating_add(sell_amount);
+
+        let refund = available.saturating_sub(sell_amount);
+        if refund > NearToken::from_yoctonear(0) {
+            self.transfer_quote(bidder, refund);
+        }
+    }
+
+    fn assert_valid_order(&self, buy_amount: U128, sell_amount: NearToken) {
         assert!(
             sell_amount > NearToken::from_yoctonear(0),
             "Sell amount must be greater than 0, tokens are not free"
@@ -161,198 +1074,1024 @@ impl Contract {
             "Buy amount must be greater than 0"
         );
 
-        let auctioner_price = self.auction.min_buy_amount.as_yoctonear() as f64
-            / self.auction.auctioned_sell_amount.0 as f64;
-        let offer_price = sell_amount.as_yoctonear() as f64 / buy_amount.0 as f64;
+        let auctioner_price = Price::new(
+            self.auction.min_buy_amount,
+            self.auction.auctioned_sell_amount,
+        );
+        let offer_price = Price::new(sell_amount, buy_amount);
         assert!(
             offer_price >= auctioner_price,
             "Offer price is less than minimum price that auctioner is willing to accept"
         );
+    }
 
-        let order = Order {
-            bidder: env::predecessor_account_id(),
-            buy_amount,
-            sell_amount,
-        };
+    pub fn set_cancellation_cutoff(&mut self, cancellation_cutoff: Option<U64>) {
+        assert!(
+            env::predecessor_account_id() == self.auction.auctioner,
+            "Only the auctioner can set the cancellation cutoff"
+        );
+        assert!(
+            self.orders.is_empty(),
+            "Cancellation cutoff can not be changed once orders are placed"
+        );
+        self.assert_status(
+            &[AuctionStatus::Scheduled, AuctionStatus::Open],
+            "Cancellation cutoff can not be changed once the auction has ended",
+        );
 
-        self.orders.push(&order);
+        self.auction.cancellation_cutoff = cancellation_cutoff;
+    }
 
-        true
+    pub fn cancel_order(&mut self, order_id: U64) -> Promise {
+        let bidder = env::predecessor_account_id();
+
+        assert!(
+            self.auction.mode == AuctionMode::Batch,
+            "Purchases of a Dutch auction are final"
+        );
+        self.assert_bidding_open();
+
+        self.assert_cancellation_open();
+
+        let (_, order) = self.find_order(order_id).expect("Order not found");
+        assert!(
+            order.bidder == bidder,
+            "Only the bidder can cancel the order"
+        );
+
+        self.remove_order(&order);
+
+        let order_cancelled_log =
+            EventLog::new(EventLogVariant::OrderCancelled(vec![OrderCancelledLog {
+                order_id,
+                bidder: bidder.clone(),
+                buy_amount: order.buy_amount,
+                sell_amount: order.sell_amount,
+            }]));
+        log!("{}", order_cancelled_log);
+
+        self.transfer_quote(bidder, order.sell_amount)
     }
 
+    fn assert_cancellation_open(&self) {
+        if let Some(cutoff) = self.auction.cancellation_cutoff {
+            assert!(
+                env::block_timestamp() < self.auction.auction_end.0.saturating_sub(cutoff.0),
+                "Orders can not be cancelled this close to the end of the auction"
+            );
+        }
+    }
+
+    // Orders are sorted by the price of the tokens (sell_amount / buy_amount) = (NearToken to pay/ amount of tokens to buy)
+    // So the order with the highest price per token is the first, orders with the same price keep their placement order.
+    fn book_key(order: &Order) -> BookKey {
+        BookKey {
+            price: order.price(),
+            order_id: order.id.0,
+        }
+    }
+
+    fn book_order(&self, key: &BookKey) -> Order {
+        self.find_order(U64(key.order_id)).unwrap().1
+    }
+
+    fn find_order(&self, order_id: U64) -> Option<(u64, Order)> {
+        self.order_positions
+            .get(&order_id.0)
+            .map(|position| (position, self.orders.get(position).unwrap()))
+    }
+
+    // The order stays in orders as a tombstone so no other order moves, it is only dropped from the
+    // indexes. Orders keep their placement order and cancelling costs the same at any position
+    fn remove_order(&mut self, removed_order: &Order) {
+        self.order_positions.remove(&removed_order.id.0);
+        self.sorted_orders.remove(&Self::book_key(removed_order));
+        self.auction.total_bids = self
+            .auction
+            .total_bids
+            .saturating_sub(removed_order.sell_amount);
+
+        let mut bidder_orders = self
+            .bidder_orders
+            .get(&removed_order.bidder)
+            .unwrap_or_default();
+        bidder_orders.retain(|order_id| *order_id != removed_order.id.0);
+        self.bidder_orders
+            .insert(&removed_order.bidder, &bidder_orders);
+    }
+
+    fn bonding_curve(&self) -> BondingCurve {
+        match &self.auction.mode {
+            AuctionMode::BondingCurve(curve) => curve.clone(),
+            _ => env::panic_str("The auction is not a bonding curve"),
+        }
+    }
+
+    // Buys amount tokens from the bonding curve, trading opens at the auction start and never closes.
+    // The attached deposit above the cost is returned, the cost can not be more than max_in
+    #[payable]
+    pub fn buy(&mut self, amount: U128, max_in: NearToken) -> NearToken {
+        let curve = self.bonding_curve();
+        let buyer = env::predecessor_account_id();
+        let deposit = self.register_from_deposit(&buyer, env::attached_deposit());
+
+        self.assert_bidding_open();
+        assert!(amount.0 > 0, "Amount must be greater than 0");
+        // The cap and the limit per account count every token the account bought from the curve,
+        // selling tokens back does not free them
+        let cap = self.assert_allowlisted(&buyer);
+        let bought = self.account_fills.get(&buyer).map_or(0, |bought| bought.0) + amount.0;
+        self.assert_within_allocation_cap(cap, bought);
+        self.assert_within_max_buy(bought);
+
+        let supply = self.auction.auctioned_sell_amount;
+        let sold = self.auction.curve_sold.0;
+        assert!(
+            amount.0 <= supply.0 - sold,
+            "Only {} tokens are left on the curve",
+            supply.0 - sold
+        );
+
+        let cost = curve.cost(supply, sold, sold + amount.0, true);
+        assert!(
+            cost <= max_in,
+            "Cost {} is above max_in",
+            cost.as_yoctonear()
+        );
+        assert!(
+            deposit >= cost,
+            "The attached deposit is too small. Required: {}",
+            cost
+        );
+
+        self.auction.curve_sold = U128(sold + amount.0);
+        self.auction.curve_reserve = self.auction.curve_reserve.saturating_add(cost);
+        if cap.is_some() || self.auction.max_buy_per_account.is_some() {
+            self.account_fills.insert(&buyer, &U128(bought));
+        }
+        self.token
+            .internal_transfer(&env::current_account_id(), &buyer, amount.0, None);
+
+        let tokens_bought_log = EventLog::new(EventLogVariant::TokensBought(vec![TradeLog {
+            account_id: buyer.clone(),
+            token_amount: amount,
+            near_amount: cost,
+            curve_sold: self.auction.curve_sold,
+        }]));
+        log!("{}", tokens_bought_log);
+
+        let refund = deposit.saturating_sub(cost);
+        if refund > NearToken::from_yoctonear(0) {
+            Promise::new(buyer).transfer(refund);
+        }
+
+        cost
+    }
+
+    // Sells amount tokens back to the bonding curve, the near paid out can not be less than min_out
+    pub fn sell(&mut self, amount: U128, min_out: NearToken) -> Promise {
+        let curve = self.bonding_curve();
+        let seller = env::predecessor_account_id();
+
+        self.assert_bidding_open();
+        assert!(amount.0 > 0, "Amount must be greater than 0");
+        let sold = self.auction.curve_sold.0;
+        assert!(
+            amount.0 <= sold,
+            "Only {} tokens can be sold back to the curve",
+            sold
+        );
+
+        let proceeds = curve.cost(
+            self.auction.auctioned_sell_amount,
+            sold - amount.0,
+            sold,
+            false,
+        );
+        assert!(
+            proceeds >= min_out,
+            "Proceeds {} are below min_out",
+            proceeds.as_yoctonear()
+        );
+
+        self.token
+            .internal_transfer(&seller, &env::current_account_id(), amount.0, None);
+        self.auction.curve_sold = U128(sold - amount.0);
+        self.auction.curve_reserve = self.auction.curve_reserve.saturating_sub(proceeds);
+
+        let tokens_sold_log = EventLog::new(EventLogVariant::TokensSold(vec![TradeLog {
+            account_id: seller.clone(),
+            token_amount: amount,
+            near_amount: proceeds,
+            curve_sold: self.auction.curve_sold,
+        }]));
+        log!("{}", tokens_sold_log);
+
+        Promise::new(seller).transfer(proceeds)
+    }
+
+    // Settles the whole book in one transaction, use settle_auction_step when the book is too big for it
     pub fn settle_auction(&mut self) {
-        log!("Auction duration: {}", self.auction.auction_duration.0);
+        log!("Auction end: {}", self.auction.auction_end.0);
         log!("Block timestamp: {}", env::block_timestamp());
 
+        assert!(self.settle_auction_step(U64(u64::MAX)));
+    }
+
+    // Processes at most max_orders orders of the book and returns true once the auction is settled.
+    // Progress is kept in state so the settlement can be spread over as many calls as needed
+    pub fn settle_auction_step(&mut self, max_orders: U64) -> bool {
+        assert!(
+            !matches!(self.auction.mode, AuctionMode::BondingCurve(_)),
+            "A bonding curve has no settlement"
+        );
+        let message = match self.current_status() {
+            AuctionStatus::Settled | AuctionStatus::Failed => "Auction already settled",
+            AuctionStatus::Cancelled => "Auction is cancelled",
+            _ => "Auction has not ended yet, cannot calculate winning orders",
+        };
+        self.assert_status(&[AuctionStatus::Ended, AuctionStatus::Settling], message);
+        // Only a sealed-bid auction waits for its reveal period, the other ones settle from the end
         assert!(
-            self.auction.auction_duration.0 < env::block_timestamp(),
-            "Auction has not ended yet, cannot calculate winning orders"
+            self.auction.sealed_bid.is_none()
+                || self.auction.reveal_end.0 <= env::block_timestamp(),
+            "Reveal period has not ended yet"
         );
-        assert!(!self.auction.is_settled, "Auction already settled");
+        assert!(max_orders.0 > 0, "Max orders must be greater than 0");
 
-        self.sort_orders();
-        self.calculate_winning_orders();
-        self.calculate_final_auction_price();
+        self.settle(max_orders.0)
+    }
 
-        self.auction.is_settled = true;
+    fn settle(&mut self, max_orders: u64) -> bool {
+        if !self.is_min_raise_reached() {
+            self.fail_auction();
+            return true;
+        }
+
+        // The purchases of a Dutch auction are already the winning orders
+        if self.auction.mode == AuctionMode::Batch {
+            self.calculate_winning_orders(max_orders);
+        } else {
+            self.settlement.phase = SettlementPhase::Finished;
+        }
+
+        if self.settlement.phase == SettlementPhase::Finished {
+            self.calculate_final_auction_price(self.settlement.clearing_price);
+            self.pay_protocol_fee();
+            self.auction.is_settled = true;
+            self.release_unsold_supply(U128(
+                self.auction.auctioned_sell_amount.0 - self.settlement.filled_amount.0,
+            ));
+            self.set_status(AuctionStatus::Settled);
+            self.emit_auction_settled();
+        } else {
+            self.set_status(AuctionStatus::Settling);
+        }
+
+        log!(
+            "Settlement phase: {:?}, tokens filled: {}",
+            self.settlement.phase,
+            self.settlement.filled_amount.0
+        );
+
+        self.auction.is_settled
     }
 
-    // Orders are sorted by the price of the tokens (sell_amount / buy_amount) = (NearToken to pay/ amount of tokens to buy)
-    // So the order with the highest price per token is the first
-    fn sort_orders(&mut self) {
-        let mut orders = self.orders.to_vec();
-        orders.sort_by(|a, b| {
-            let price_a = (a.sell_amount.as_yoctonear() as f64) / (a.buy_amount.0 as f64);
-            let price_b = (b.sell_amount.as_yoctonear() as f64) / (b.buy_amount.0 as f64);
-            price_b
-                .partial_cmp(&price_a)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-        self.orders.clear();
-        for order in orders {
-            self.orders.push(&order);
+    // Calls off an auction nobody has bid in yet, the whole supply is released like an unsold supply
+    pub fn cancel_auction(&mut self) {
+        assert!(
+            env::predecessor_account_id() == self.auction.auctioner,
+            "Only the auctioner can cancel the auction"
+        );
+        self.assert_status(
+            &[AuctionStatus::Scheduled, AuctionStatus::Open],
+            "Only an auction that has not ended can be cancelled",
+        );
+        assert!(
+            self.sorted_orders.is_empty()
+                && self.commitments.is_empty()
+                && self.auction.curve_reserve == NearToken::from_yoctonear(0),
+            "An auction can not be cancelled once it has bids"
+        );
+
+        self.set_status(AuctionStatus::Cancelled);
+        self.release_unsold_supply(self.auction.auctioned_sell_amount);
+
+        log!("Auction cancelled by @{}", self.auction.auctioner);
+    }
+
+    fn assert_settled(&mut self) {
+        self.assert_status(
+            &[AuctionStatus::Settled, AuctionStatus::Failed],
+            "Auction not settled yet",
+        );
+    }
+
+    fn is_min_raise_reached(&self) -> bool {
+        self.auction
+            .min_raise
+            .is_none_or(|min_raise| self.auction.total_bids >= min_raise)
+    }
+
+    // Nothing is sold, every order is refunded in full and the whole supply is released
+    fn fail_auction(&mut self) {
+        log!(
+            "Auction failed, bids: {} are below the minimum raise",
+            self.auction.total_bids
+        );
+
+        // The purchases of a Dutch auction are undone so they are refunded like every other order
+        for index in 0..self.winning_orders.len() {
+            let (order, _) = self.winning_orders.get(index).unwrap();
+            self.winning_positions.remove(&order.id.0);
         }
+        self.winning_orders.clear();
+        self.settlement.filled_amount = U128(0);
+        self.settlement.clearing_price = None;
+
+        self.settlement.phase = SettlementPhase::Finished;
+        self.auction.is_failed = true;
+        self.auction.is_settled = true;
+        self.release_unsold_supply(self.auction.auctioned_sell_amount);
+        self.set_status(AuctionStatus::Failed);
+        self.emit_auction_settled();
+    }
+
+    fn emit_auction_settled(&self) {
+        let auction_settled_log =
+            EventLog::new(EventLogVariant::AuctionSettled(vec![AuctionSettledLog {
+                is_failed: self.auction.is_failed,
+                clearing_price: self.auction.clearing_price,
+                final_auction_price: self.auction.final_auction_price,
+                filled_amount: self.settlement.filled_amount,
+                unsold_amount: self.auction.unsold_amount,
+                total_proceeds: self.auction.total_proceeds,
+                winning_orders: U64(self.winning_orders.len()),
+            }]));
+        log!("{}", auction_settled_log);
     }
 
-    fn calculate_winning_orders(&mut self) {
-        let mut sum_sell_tokens = U128(0);
+    fn release_unsold_supply(&mut self, amount: U128) {
+        self.auction.unsold_amount = amount;
+        if amount.0 == 0 {
+            return;
+        }
 
-        for order in self.orders.to_vec() {
-            let new_sum = Self::add(sum_sell_tokens, order.buy_amount);
+        let auctioner = self.auction.auctioner.clone();
 
-            if new_sum.0 <= self.auction.auctioned_sell_amount.0 {
-                sum_sell_tokens = new_sum;
-                self.auction.winning_orders.push((order.clone(), false));
-            } else {
-                let remaining_tokens = self
-                    .auction
-                    .auctioned_sell_amount
-                    .0
-                    .saturating_sub(sum_sell_tokens.0);
-                if remaining_tokens > 0 {
-                    let final_order = Order {
-                        bidder: order.bidder,
-                        buy_amount: U128(remaining_tokens),
-                        sell_amount: NearToken::from_yoctonear(
-                            (order.sell_amount.as_yoctonear() * remaining_tokens)
-                                / order.buy_amount.0,
-                        ),
-                    };
-                    self.auction.winning_orders.push((final_order, false));
+        match self.auction.unsold_supply {
+            // The tokens are already in the account of the auctioner when it is the contract itself
+            UnsoldSupply::ReturnToAuctioner if auctioner == env::current_account_id() => {}
+            UnsoldSupply::ReturnToAuctioner => {
+                if !self.token.accounts.contains_key(&auctioner) {
+                    self.token.internal_register_account(&auctioner);
                 }
+                self.token.internal_transfer(
+                    &env::current_account_id(),
+                    &auctioner,
+                    amount.0,
+                    Some("Unsold tokens returned to the auctioner".to_string()),
+                );
+            }
+            UnsoldSupply::Burn => {
+                self.token
+                    .internal_withdraw(&env::current_account_id(), amount.0);
+                near_contract_standards::fungible_token::events::FtBurn {
+                    owner_id: &env::current_account_id(),
+                    amount,
+                    memo: Some("Unsold tokens are burned"),
+                }
+                .emit();
+            }
+            UnsoldSupply::KeepInTreasury => {}
+        }
+    }
+
+    // Walks the order book from the best price, at most budget orders per call. The price of the last
+    // (lowest priced) winning order is the clearing price, the walk stops once every token is sold
+    // so the cost of the settlement grows with the winning orders only
+    fn calculate_winning_orders(&mut self, budget: u64) {
+        let total_tokens = self.auction.auctioned_sell_amount;
+        let mut next_key = match self.settlement.last_cleared {
+            Some(last_cleared) => self.sorted_orders.higher(&last_cleared),
+            None => self.sorted_orders.min(),
+        };
+        let mut processed = 0;
+
+        while let Some(key) = next_key {
+            if processed == budget || self.settlement.filled_amount == total_tokens {
                 break;
             }
+
+            let order = self.book_order(&key);
+            let new_sum = Self::add(self.settlement.filled_amount, order.buy_amount);
+
+            if new_sum.0 <= total_tokens.0 {
+                self.settlement.filled_amount = new_sum;
+                self.push_winning_order(order.clone());
+            } else {
+                let remaining_tokens = total_tokens.0 - self.settlement.filled_amount.0;
+                // The whole deposit is kept so the unfilled part is returned on claim
+                let final_order = Order {
+                    id: order.id,
+                    bidder: order.bidder.clone(),
+                    buy_amount: U128(remaining_tokens),
+                    sell_amount: order.sell_amount,
+                };
+                self.settlement.filled_amount = total_tokens;
+                self.push_winning_order(final_order);
+            }
+
+            self.settlement.clearing_price = Some(order.price());
+            self.settlement.last_cleared = Some(key);
+            processed += 1;
+            next_key = self.sorted_orders.higher(&key);
+        }
+
+        if next_key.is_none() || self.settlement.filled_amount == total_tokens {
+            self.settlement.phase = SettlementPhase::Finished;
         }
     }
 
+    fn push_winning_order(&mut self, order: Order) {
+        self.winning_positions
+            .insert(&order.id.0, &self.winning_orders.len());
+        self.winning_orders.push(&(order, false));
+    }
+
     fn add(a: U128, b: U128) -> U128 {
         U128(a.0.checked_add(b.0).expect("Math overflow"))
     }
 
-    fn calculate_final_auction_price(&mut self) {
-        let last_order = self.auction.winning_orders.last().unwrap().0.clone();
+    // An auction without winning orders settles with no clearing price, a final price of 0 and no proceeds
+    fn calculate_final_auction_price(&mut self, clearing_price: Option<Price>) {
+        if let Some(clearing_price) = clearing_price {
+            self.auction.final_auction_price = clearing_price.per_token();
+            self.auction.clearing_price = Some(clearing_price);
+            self.auction.total_proceeds = clearing_price.cost_of(self.settlement.filled_amount);
+        }
+    }
 
-        let sell_amount_yocto = last_order.sell_amount.as_yoctonear();
+    // The fee is paid out of the proceeds as soon as the auction is settled
+    fn pay_protocol_fee(&mut self) {
+        let Some(protocol_fee) = self.auction.protocol_fee.clone() else {
+            return;
+        };
 
-        let buy_amount_u128 = last_order.buy_amount.0;
+        let fee_amount = NearToken::from_yoctonear(mul_div(
+            self.auction.total_proceeds.as_yoctonear(),
+            protocol_fee.fee_bps.into(),
+            MAX_FEE_BPS.into(),
+        ));
+        if fee_amount == NearToken::from_yoctonear(0) {
+            return;
+        }
+        self.auction.fee_amount = fee_amount;
 
-        let price_per_token_yocto = sell_amount_yocto / buy_amount_u128;
+        let protocol_fee_paid_log =
+            EventLog::new(EventLogVariant::ProtocolFeePaid(vec![ProtocolFeePaidLog {
+                recipient: protocol_fee.recipient.clone(),
+                fee_bps: protocol_fee.fee_bps,
+                amount: fee_amount,
+            }]));
+        log!("{}", protocol_fee_paid_log);
 
-        self.auction.final_auction_price = NearToken::from_yoctonear(price_per_token_yocto);
+        self.transfer_quote(protocol_fee.recipient, fee_amount);
     }
 
+    // Claims one winning order per call, claim_all settles every winning order of the caller
     pub fn claim_tokens(&mut self) {
-        let claimer = env::predecessor_account_id();
+        self.claim_tokens_for(env::predecessor_account_id());
+    }
 
-        assert!(self.auction.is_settled, "Auction not settled yet");
+    // Anyone can claim on behalf of a bidder, the tokens and the NEAR to return always go to the bidder
+    pub fn claim_tokens_for(&mut self, account_id: AccountId) {
+        self.assert_settled();
 
-        let (order_index, order) = self
-            .find_unclaimed_winning_order(&claimer)
+        let (order_index, _) = self
+            .find_unclaimed_winning_order(&account_id)
             .expect("You are not allowed to claim or have already claimed");
 
-        self.auction.winning_orders[order_index].1 = true;
+        self.pay_out_winning_orders(&account_id, &[order_index]);
+    }
+
+    pub fn claim_all(&mut self) {
+        let claimer = env::predecessor_account_id();
 
-        self.token.internal_transfer(
-            &env::current_account_id(),
-            &claimer,
-            order.buy_amount.into(),
-            None,
+        self.assert_settled();
+
+        let order_indexes: Vec<u64> = self
+            .find_winning_orders(&claimer)
+            .into_iter()
+            .filter(|(_, _, claimed)| !claimed)
+            .map(|(index, _, _)| index)
+            .collect();
+        assert!(
+            !order_indexes.is_empty(),
+            "You are not allowed to claim or have already claimed"
         );
 
-        let refund = self.calculate_near_to_return(&order);
+        self.pay_out_winning_orders(&claimer, &order_indexes);
+    }
+
+    // Transfers the tokens of the winning orders and returns the NEAR paid above the final price in one go
+    fn pay_out_winning_orders(&mut self, claimer: &AccountId, order_indexes: &[u64]) {
+        let mut tokens = U128(0);
+        let mut refund = NearToken::from_yoctonear(0);
+        let mut order_ids = Vec::new();
+
+        for &order_index in order_indexes {
+            let (order, _) = self.winning_orders.get(order_index).unwrap();
+            self.winning_orders
+                .replace(order_index, &(order.clone(), true));
+
+            tokens = Self::add(tokens, order.buy_amount);
+            refund = refund.saturating_add(self.calculate_near_to_return(&order));
+            order_ids.push(order.id);
+        }
+
+        let tokens_claimed_log =
+            EventLog::new(EventLogVariant::TokensClaimed(vec![TokensClaimedLog {
+                account_id: claimer.clone(),
+                order_ids,
+                token_amount: tokens,
+                refund,
+            }]));
+        log!("{}", tokens_claimed_log);
+
+        self.token
+            .internal_transfer(&env::current_account_id(), claimer, tokens.into(), None);
 
         if refund > NearToken::from_yoctonear(0) {
-            Promise::new(claimer.clone()).transfer(refund);
+            self.transfer_quote(claimer.clone(), refund);
         }
     }
 
-    fn find_unclaimed_winning_order(&self, claimer: &AccountId) -> Option<(usize, Order)> {
-        self.auction
-            .winning_orders
-            .iter()
-            .enumerate()
-            .find(|(_, (order, claimed))| order.bidder == *claimer && !claimed)
-            .map(|(index, (order, _))| (index, order.clone()))
+    fn find_unclaimed_winning_order(&self, claimer: &AccountId) -> Option<(u64, Order)> {
+        self.find_winning_orders(claimer)
+            .into_iter()
+            .find(|(_, _, claimed)| !claimed)
+            .map(|(index, order, _)| (index, order))
     }
 
+    // Winning orders of the bidder with their position in winning_orders and their claimed flag
+    fn find_winning_orders(&self, bidder: &AccountId) -> Vec<(u64, Order, bool)> {
+        self.bidder_orders
+            .get(bidder)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|order_id| self.winning_positions.get(&order_id))
+            .map(|index| {
+                let (order, claimed) = self.winning_orders.get(index).unwrap();
+                (index, order, claimed)
+            })
+            .collect()
+    }
+
+    // The cost of every order is rounded up so the refunds never take from the proceeds of the auctioner
     #[allow(clippy::missing_const_for_fn)]
     fn calculate_near_to_return(&self, order: &Order) -> NearToken {
-        let final_price = self.auction.final_auction_price;
-        let tokens_to_buy = order.buy_amount.0;
-        let total_cost = final_price.saturating_mul(tokens_to_buy);
+        let total_cost = self
+            .auction
+            .clearing_price
+            .map_or(NearToken::from_yoctonear(0), |price| {
+                price.cost_of_rounded_up(order.buy_amount)
+            });
         order.sell_amount.saturating_sub(total_cost)
     }
 
-    pub fn refund_deposit(&mut self) -> Promise {
-        assert!(self.auction.is_settled, "Auction not settled yet");
-        let claimer = env::predecessor_account_id();
+    // Sends the auctioner the proceeds, minus the protocol fee, that have not been withdrawn yet. The deposits
+    // that still have to be refunded to the bidders are not part of the proceeds, so they stay in the contract
+    pub fn withdraw_proceeds(&mut self) -> Promise {
+        let auctioner = env::predecessor_account_id();
 
         assert!(
-            self.orders.iter().any(|order| order.bidder == claimer),
-            "No order found for this account"
+            auctioner == self.auction.auctioner,
+            "Only the auctioner can withdraw the proceeds"
         );
+        self.assert_settled();
 
+        let proceeds = self
+            .auction
+            .total_proceeds
+            .saturating_sub(self.auction.fee_amount)
+            .saturating_sub(self.auction.withdrawn_proceeds);
         assert!(
-            !self
-                .auction
-                .refunded_orders
-                .iter()
-                .any(|order| order.bidder == claimer),
-            "Refund has already been claimed"
+            proceeds > NearToken::from_yoctonear(0),
+            "No proceeds to withdraw"
         );
 
+        self.auction.withdrawn_proceeds = self.auction.withdrawn_proceeds.saturating_add(proceeds);
+        log!("Auctioner @{} withdraws {}", auctioner, proceeds);
+
+        self.transfer_quote(auctioner, proceeds)
+    }
+
+    // Refunds one losing order per call, refund_all refunds every losing order of the caller
+    pub fn refund_deposit(&mut self) -> Promise {
+        self.refund_deposit_for(env::predecessor_account_id())
+    }
+
+    // Anyone can trigger the refund of a bidder, the deposit always goes back to the bidder
+    pub fn refund_deposit_for(&mut self, account_id: AccountId) -> Promise {
+        self.assert_settled();
+
+        let order = self
+            .find_refundable_orders(&account_id)
+            .into_iter()
+            .next()
+            .expect("No refundable order found for this account");
+
+        self.refund_orders(account_id, &[order])
+    }
+
+    pub fn refund_all(&mut self) -> Promise {
+        self.assert_settled();
+        let claimer = env::predecessor_account_id();
+
+        let orders = self.find_refundable_orders(&claimer);
         assert!(
-            !self
-                .auction
-                .winning_orders
-                .iter()
-                .any(|(order, _)| order.bidder == claimer),
-            "Winning orders cannot claim refund"
+            !orders.is_empty(),
+            "No refundable order found for this account"
         );
 
-        let order = self
-            .orders
-            .iter()
-            .find(|order| order.bidder == claimer)
-            .expect("No order found for this account");
+        self.refund_orders(claimer, &orders)
+    }
 
-        self.auction.refunded_orders.push(order.clone());
+    // Returns the whole deposit of losing orders of the same bidder in one transfer
+    fn refund_orders(&mut self, bidder: AccountId, orders: &[Order]) -> Promise {
+        let mut refund = NearToken::from_yoctonear(0);
+        for order in orders {
+            refund = refund.saturating_add(order.sell_amount);
+            self.push_refunded_order(order);
+        }
+
+        let deposit_refunded_log =
+            EventLog::new(EventLogVariant::DepositRefunded(vec![DepositRefundedLog {
+                account_id: bidder.clone(),
+                order_ids: orders.iter().map(|order| order.id).collect(),
+                amount: refund,
+            }]));
+        log!("{}", deposit_refunded_log);
+
+        self.transfer_quote(bidder, refund)
+    }
+
+    // Orders of the bidder that did not win and have not been refunded yet
+    fn find_refundable_orders(&self, bidder: &AccountId) -> Vec<Order> {
+        let orders = self.find_bidder_orders(bidder);
+        assert!(!orders.is_empty(), "No order found for this account");
+
+        orders
+            .into_iter()
+            .filter(|order| self.is_refundable(order))
+            .collect()
+    }
 
-        Promise::new(claimer).transfer(order.sell_amount)
+    fn is_refundable(&self, order: &Order) -> bool {
+        !self.is_cancelled(order)
+            && !self.winning_positions.contains_key(&order.id.0)
+            && !self.refunded_positions.contains_key(&order.id.0)
+    }
+
+    fn push_refunded_order(&mut self, order: &Order) {
+        self.refunded_positions
+            .insert(&order.id.0, &self.refunded_orders.len());
+        self.refunded_orders.push(order);
+    }
+
+    // Pays out the winning orders and then refunds the losing orders, at most batch_size orders per call, so
+    // bidders do not have to come back to claim. Anyone can call it, returns true once every order is processed
+    pub fn distribute(&mut self, batch_size: U64) -> bool {
+        self.assert_settled();
+        assert!(batch_size.0 > 0, "Batch size must be greater than 0");
+
+        let mut budget = batch_size.0;
+
+        while budget > 0 && self.distribution.next_winning_order.0 < self.winning_orders.len() {
+            let index = self.distribution.next_winning_order.0;
+            let (order, claimed) = self.winning_orders.get(index).unwrap();
+            // A bidder that unregistered from the token can not receive it, the order is left to be claimed
+            if !claimed && self.token.accounts.contains_key(&order.bidder) {
+                self.pay_out_winning_orders(&order.bidder, &[index]);
+            }
+
+            self.distribution.next_winning_order = U64(index + 1);
+            budget -= 1;
+        }
+
+        while budget > 0 && self.distribution.next_order.0 < self.orders.len() {
+            let position = self.distribution.next_order.0;
+            let order = self.orders.get(position).unwrap();
+            if self.is_refundable(&order) {
+                self.refund_orders(order.bidder.clone(), &[order]);
+            }
+
+            self.distribution.next_order = U64(position + 1);
+            budget -= 1;
+        }
+
+        log!(
+            "Distribution: {} of {} winning orders and {} of {} orders processed",
+            self.distribution.next_winning_order.0,
+            self.winning_orders.len(),
+            self.distribution.next_order.0,
+            self.orders.len()
+        );
+
+        self.distribution.next_winning_order.0 == self.winning_orders.len()
+            && self.distribution.next_order.0 == self.orders.len()
+    }
+
+    // Sends an amount in the currency of the bids: NEAR, or the quote token with a callback that keeps
+    // the amount for the receiver when the transfer fails, e.g. because the receiver is not registered in it
+    fn transfer_quote(&self, receiver_id: AccountId, amount: NearToken) -> Promise {
+        let Some(quote_token) = self.auction.quote_token.clone() else {
+            return Promise::new(receiver_id).transfer(amount);
+        };
+
+        ext_ft_core::ext(quote_token)
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(receiver_id.clone(), U128(amount.as_yoctonear()), None)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_QUOTE_TRANSFER)
+                    .resolve_quote_transfer(receiver_id, amount),
+            )
+    }
+
+    #[private]
+    pub fn resolve_quote_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        amount: NearToken,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_err() {
+            let unpaid = self.unpaid_transfers.get(&receiver_id).unwrap_or_default();
+            self.unpaid_transfers
+                .insert(&receiver_id, &unpaid.saturating_add(amount));
+            log!(
+                "Transfer of {} to @{} failed, it can be retried with retry_unpaid_transfer",
+                amount.as_yoctonear(),
+                receiver_id
+            );
+        }
+    }
+
+    // Sends again the quote token transfers of the account that failed
+    pub fn retry_unpaid_transfer(&mut self, account_id: AccountId) -> Promise {
+        let amount = self
+            .unpaid_transfers
+            .remove(&account_id)
+            .expect("No unpaid transfer found for this account");
+
+        self.transfer_quote(account_id, amount)
+    }
+
+    pub fn get_unpaid_transfer(&self, account_id: AccountId) -> NearToken {
+        self.unpaid_transfers.get(&account_id).unwrap_or_default()
+    }
+
+    fn find_bidder_orders(&self, bidder: &AccountId) -> Vec<Order> {
+        self.bidder_orders
+            .get(bidder)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|order_id| self.find_order(U64(order_id)))
+            .map(|(_, order)| order)
+            .collect()
     }
 
     //Get info about the auction
 
-    pub fn get_orders(&self) -> Vec<Order> {
-        self.orders.to_vec()
+    // Open orders in placement order. from_index and limit count every order placed, so a page has
+    // fewer orders when some of them were cancelled. Without limit every order from from_index on is returned
+    pub fn get_orders(&self, from_index: Option<U64>, limit: Option<U64>) -> Vec<Order> {
+        let from_index = from_index.map_or(0, |index| index.0);
+        let limit = limit.map_or(self.orders.len(), |limit| limit.0);
+
+        (from_index..self.orders.len())
+            .take(limit as usize)
+            .map(|index| self.orders.get(index).unwrap())
+            .filter(|order| !self.is_cancelled(order))
+            .collect()
+    }
+
+    fn is_cancelled(&self, order: &Order) -> bool {
+        !self.order_positions.contains_key(&order.id.0)
+    }
+
+    pub fn get_orders_by_bidder(&self, account_id: AccountId) -> Vec<Order> {
+        self.find_bidder_orders(&account_id)
+    }
+
+    pub fn get_order(&self, order_id: U64) -> Option<Order> {
+        self.find_order(order_id).map(|(_, order)| order)
     }
 
+    // Orders from the best price to the worst, without limit every order from from_index on is returned
+    pub fn get_order_book(&self, from_index: Option<U64>, limit: Option<U64>) -> Vec<Order> {
+        let from_index = from_index.map_or(0, |index| index.0);
+        let limit = limit.map_or(self.sorted_orders.len(), |limit| limit.0);
+
+        self.sorted_orders
+            .iter()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .map(|(key, _)| self.book_order(&key))
+            .collect()
+    }
+
+    // Price the auction would clear at if it ended now, None while the book is empty.
+    // Only the orders that would win are read
+    pub fn get_current_clearing_price(&self) -> Option<Price> {
+        let total_tokens = self.auction.auctioned_sell_amount.0;
+        let mut filled_amount = 0u128;
+        let mut clearing_price = None;
+
+        for (key, _) in self.sorted_orders.iter() {
+            if filled_amount >= total_tokens {
+                break;
+            }
+            let order = self.book_order(&key);
+            filled_amount = filled_amount.saturating_add(order.buy_amount.0);
+            clearing_price = Some(key.price);
+        }
+
+        clearing_price
+    }
+
+    // Near to pay for amount tokens on the bonding curve
+    pub fn get_buy_price(&self, amount: U128) -> NearToken {
+        let sold = self.auction.curve_sold.0;
+        self.bonding_curve().cost(
+            self.auction.auctioned_sell_amount,
+            sold,
+            sold.checked_add(amount.0).expect("Math overflow"),
+            true,
+        )
+    }
+
+    // Near paid out for selling amount tokens back to the bonding curve
+    pub fn get_sell_price(&self, amount: U128) -> NearToken {
+        let sold = self.auction.curve_sold.0;
+        self.bonding_curve().cost(
+            self.auction.auctioned_sell_amount,
+            sold.checked_sub(amount.0).expect("Math overflow"),
+            sold,
+            false,
+        )
+    }
+
+    // Current price of a Dutch auction, None for a batch auction
+    pub fn get_dutch_auction_price(&self) -> Option<Price> {
+        let AuctionMode::Dutch(dutch) = &self.auction.mode else {
+            return None;
+        };
+
+        Some(dutch.price_at(
+            self.auction.min_buy_amount,
+            self.auction.auctioned_sell_amount,
+            env::block_timestamp().saturating_sub(self.auction.auction_start.0),
+            self.auction.auction_end.0 - self.auction.auction_start.0,
+        ))
+    }
+
+    // The stored status only changes on calls, the one returned here follows the clock like get_auction_status
     pub fn get_auction_info(&self) -> Auction {
-        self.auction.clone()
+        let status = self.get_auction_status();
+        Auction {
+            status: status.status,
+            status_changed_at: status.since,
+            ..self.auction.clone()
+        }
+    }
+
+    pub fn get_auction_summary(&self) -> AuctionSummary {
+        AuctionSummary {
+            auctioner: self.auction.auctioner.clone(),
+            auction_start: self.auction.auction_start,
+            auction_end: self.auction.auction_end,
+            auctioned_sell_amount: self.auction.auctioned_sell_amount,
+            min_buy_amount: self.auction.min_buy_amount,
+            is_settled: self.auction.is_settled,
+            is_failed: self.auction.is_failed,
+            final_auction_price: self.auction.final_auction_price,
+            clearing_price: self.auction.clearing_price,
+            cancellation_cutoff: self.auction.cancellation_cutoff,
+            min_raise: self.auction.min_raise,
+            total_bids: self.auction.total_bids,
+            unsold_supply: self.auction.unsold_supply,
+            unsold_amount: self.auction.unsold_amount,
+            total_proceeds: self.auction.total_proceeds,
+            withdrawn_proceeds: self.auction.withdrawn_proceeds,
+            protocol_fee: self.auction.protocol_fee.clone(),
+            fee_amount: self.auction.fee_amount,
+            quote_token: self.auction.quote_token.clone(),
+            sealed_bid: self.auction.sealed_bid.clone(),
+            reveal_end: self.auction.reveal_end,
+            mode: self.auction.mode.clone(),
+            curve_sold: self.auction.curve_sold,
+            curve_reserve: self.auction.curve_reserve,
+            allowlist_root: self.auction.allowlist_root.clone(),
+            max_buy_per_account: self.auction.max_buy_per_account,
+            soft_close: self.auction.soft_close.clone(),
+            scheduled_end: self.auction.scheduled_end,
+            status: self.current_status(),
+            total_orders: U64(self.sorted_orders.len()),
+            total_winning_orders: U64(self.winning_orders.len()),
+            total_refunded_orders: U64(self.refunded_orders.len()),
+        }
+    }
+
+    pub fn get_auction_status(&self) -> AuctionStatusView {
+        let status = self.current_status();
+        let since = match status {
+            AuctionStatus::Open => self.auction.auction_start,
+            AuctionStatus::Ended => self.auction.auction_end,
+            _ => self.auction.status_changed_at,
+        };
+
+        AuctionStatusView {
+            status,
+            since,
+            auction_start: self.auction.auction_start,
+            auction_end: self.auction.auction_end,
+            reveal_end: self.auction.reveal_end,
+        }
     }
 
-    pub fn get_auction_winner(&self) -> Vec<(Order, bool)> {
-        self.auction.winning_orders.clone()
+    pub fn get_auction_countdown(&self) -> AuctionCountdown {
+        let now = env::block_timestamp();
+        AuctionCountdown {
+            auction_start: self.auction.auction_start,
+            auction_end: self.auction.auction_end,
+            starts_in: U64(self.auction.auction_start.0.saturating_sub(now)),
+            ends_in: U64(self.auction.auction_end.0.saturating_sub(now)),
+        }
+    }
+
+    // Without limit every winning order from from_index on is returned
+    pub fn get_auction_winner(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<U64>,
+    ) -> Vec<(Order, bool)> {
+        let from_index = from_index.map_or(0, |index| index.0);
+        let limit = limit.map_or(self.winning_orders.len(), |limit| limit.0);
+
+        (from_index..self.winning_orders.len())
+            .take(limit as usize)
+            .map(|index| self.winning_orders.get(index).unwrap())
+            .collect()
+    }
+
+    // Without limit every refunded order from from_index on is returned
+    pub fn get_refunded_orders(&self, from_index: Option<U64>, limit: Option<U64>) -> Vec<Order> {
+        let from_index = from_index.map_or(0, |index| index.0);
+        let limit = limit.map_or(self.refunded_orders.len(), |limit| limit.0);
+
+        (from_index..self.refunded_orders.len())
+            .take(limit as usize)
+            .map(|index| self.refunded_orders.get(index).unwrap())
+            .collect()
+    }
+
+    // Without limit every commitment from from_index on is returned
+    pub fn get_commitments(&self, from_index: Option<U64>, limit: Option<U64>) -> Vec<Commitment> {
+        let from_index = from_index.map_or(0, |index| index.0);
+        let limit = limit.map_or(self.commitments.len(), |limit| limit.0);
+
+        (from_index..self.commitments.len())
+            .take(limit as usize)
+            .map(|index| self.commitments.get(index).unwrap())
+            .collect()
+    }
+
+    // Hash the bidder commits for an order, the salt is kept secret by the bidder until the reveal
+    pub fn get_order_hash(
+        &self,
+        bidder: AccountId,
+        buy_amount: U128,
+        sell_amount: NearToken,
+        salt: Base64VecU8,
+    ) -> Base64VecU8 {
+        Base64VecU8(hash_order(&bidder, buy_amount, sell_amount, &salt.0))
+    }
+
+    pub fn get_distribution(&self) -> Distribution {
+        self.distribution.clone()
+    }
+
+    // Tokens that were not sold, 0 until the auction is settled
+    pub fn get_unsold_amount(&self) -> U128 {
+        self.auction.unsold_amount
+    }
+
+    pub fn get_settlement(&self) -> Settlement {
+        self.settlement.clone()
     }
 
     #[allow(clippy::missing_const_for_fn)]
@@ -407,6 +2146,39 @@ impl FungibleTokenResolver for Contract {
     }
 }
 
+// Bids in the quote token: the msg of the ft_transfer_call is a BidMessage and the transferred amount
+// is the sell amount of the order. Any failure panics, so the quote token refunds the whole transfer
+#[near]
+impl FungibleTokenReceiver for Contract {
+    fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        assert!(
+            self.auction.quote_token.as_ref() == Some(&env::predecessor_account_id()),
+            "Bids are only accepted in the quote token of the auction"
+        );
+        assert!(
+            self.token.accounts.contains_key(&sender_id),
+            "Account is not registered in token"
+        );
+
+        let bid: BidMessage =
+            serde_json::from_str(&msg).expect("Invalid bid message, expected {\"buy_amount\"}");
+
+        self.assert_bidding_open();
+        self.internal_place_order(
+            sender_id,
+            bid.buy_amount,
+            NearToken::from_yoctonear(amount.0),
+        );
+
+        PromiseOrValue::Value(U128(0))
+    }
+}
+
 #[near]
 impl StorageManagement for Contract {
     #[payable]
@@ -0,0 +1,168 @@
+use near_sdk::json_types::U128;
+use near_sdk::{near, NearToken};
+use std::cmp::Ordering;
+
+#[allow(clippy::manual_div_ceil)]
+mod u256 {
+    uint::construct_uint! {
+        /// 256-bit unsigned integer used to cross-multiply u128 amounts without overflow.
+        pub struct U256(4);
+    }
+}
+pub use u256::U256;
+
+/// Exact price of a token expressed as the ratio `sell_amount / buy_amount`
+/// (yoctoNEAR paid / tokens bought). Prices are never reduced to a float or a
+/// truncated integer, so two prices compare equal only if the ratios are equal.
+#[near(serializers = [json, borsh])]
+#[derive(Clone, Copy, Debug)]
+pub struct Price {
+    pub sell_amount: NearToken,
+    pub buy_amount: U128,
+}
+
+impl Price {
+    pub fn new(sell_amount: NearToken, buy_amount: U128) -> Self {
+        assert!(buy_amount.0 > 0, "Price denominator must be greater than 0");
+        Self {
+            sell_amount,
+            buy_amount,
+        }
+    }
+
+    /// Cost in yoctoNEAR of `amount` tokens at this price, rounded down.
+    pub fn cost_of(&self, amount: U128) -> NearToken {
+        NearToken::from_yoctonear(mul_div(
+            self.sell_amount.as_yoctonear(),
+            amount.0,
+            self.buy_amount.0,
+        ))
+    }
+
+    /// Cost in yoctoNEAR of `amount` tokens at this price, rounded up. Charging every order its
+    /// rounded up cost keeps the sum of the orders' costs at or above `cost_of` the whole amount.
+    pub fn cost_of_rounded_up(&self, amount: U128) -> NearToken {
+        NearToken::from_yoctonear(mul_div_ceil(
+            self.sell_amount.as_yoctonear(),
+            amount.0,
+            self.buy_amount.0,
+        ))
+    }
+
+    /// Price per single token in yoctoNEAR, rounded down.
+    pub fn per_token(&self) -> NearToken {
+        NearToken::from_yoctonear(self.sell_amount.as_yoctonear() / self.buy_amount.0)
+    }
+}
+
+impl PartialEq for Price {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Price {}
+
+impl PartialOrd for Price {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Price {
+    // a/b <=> c/d  is equivalent to  a*d <=> c*b  for positive denominators
+    fn cmp(&self, other: &Self) -> Ordering {
+        let lhs = U256::from(self.sell_amount.as_yoctonear()) * U256::from(other.buy_amount.0);
+        let rhs = U256::from(other.sell_amount.as_yoctonear()) * U256::from(self.buy_amount.0);
+        lhs.cmp(&rhs)
+    }
+}
+
+/// Computes `a * b / c` rounded down, using a 256-bit intermediate product.
+pub fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    let result = U256::from(a) * U256::from(b) / U256::from(c);
+    assert!(result <= U256::from(u128::MAX), "Math overflow");
+    result.as_u128()
+}
+
+/// Computes `a * b / c` rounded up, using a 256-bit intermediate product.
+pub fn mul_div_ceil(a: u128, b: u128, c: u128) -> u128 {
+    let c = U256::from(c);
+    let result = (U256::from(a) * U256::from(b) + c - 1) / c;
+    assert!(result <= U256::from(u128::MAX), "Math overflow");
+    result.as_u128()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cmp_prices_below_f64_precision() {
+        let one_near = 10u128.pow(24);
+        let higher = Price::new(NearToken::from_yoctonear(10 * one_near + 1), U128(one_near));
+        let lower = Price::new(NearToken::from_yoctonear(10 * one_near), U128(one_near));
+
+        // Both ratios collapse to the same f64, the exact comparison still ranks them
+        assert_eq!(
+            (10 * one_near + 1) as f64 / one_near as f64,
+            (10 * one_near) as f64 / one_near as f64
+        );
+        assert!(higher > lower);
+    }
+
+    #[test]
+    fn test_equivalent_fractions_are_equal() {
+        let a = Price::new(NearToken::from_yoctonear(2), U128(4));
+        let b = Price::new(NearToken::from_yoctonear(1), U128(2));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_cmp_does_not_overflow() {
+        let a = Price::new(NearToken::from_yoctonear(u128::MAX), U128(u128::MAX - 1));
+        let b = Price::new(NearToken::from_yoctonear(u128::MAX - 1), U128(u128::MAX));
+        assert!(a > b);
+    }
+
+    #[test]
+    fn test_cost_of() {
+        let price = Price::new(NearToken::from_near(55), U128(50));
+        assert_eq!(price.cost_of(U128(40)), NearToken::from_near(44));
+        assert_eq!(
+            price.per_token(),
+            NearToken::from_yoctonear(1_100_000_000_000_000_000_000_000)
+        );
+    }
+
+    #[test]
+    fn test_cost_of_rounded_up() {
+        let price = Price::new(NearToken::from_yoctonear(10), U128(3));
+        assert_eq!(price.cost_of(U128(1)), NearToken::from_yoctonear(3));
+        assert_eq!(
+            price.cost_of_rounded_up(U128(1)),
+            NearToken::from_yoctonear(4)
+        );
+        assert_eq!(
+            price.cost_of_rounded_up(U128(3)),
+            NearToken::from_yoctonear(10)
+        );
+    }
+
+    #[test]
+    fn test_mul_div_with_wide_intermediate() {
+        assert_eq!(mul_div(u128::MAX, 10, 10), u128::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "Math overflow")]
+    fn test_mul_div_overflow() {
+        mul_div(u128::MAX, 2, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Price denominator must be greater than 0")]
+    fn test_zero_denominator() {
+        Price::new(NearToken::from_yoctonear(1), U128(0));
+    }
+}
@@ -0,0 +1,39 @@
+use near_sdk::json_types::{Base64VecU8, U128, U64};
+use near_sdk::{env, near, AccountId, NearToken};
+
+/// Sealed-bid mode: bids are committed as a hash during the bidding window and revealed after it.
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct SealedBid {
+    pub reveal_duration: U64, // nanoseconds after the auction end during which the commitments are revealed
+    pub penalty_bps: u16,     // part of the deposit kept from a commitment that is never revealed
+}
+
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct Commitment {
+    pub id: U64,
+    pub bidder: AccountId,
+    pub hash: Base64VecU8,  // see `hash_order`
+    pub deposit: NearToken, // escrow that covers the sell amount of the hidden order
+    pub revealed: bool,
+    pub refunded: bool, // the deposit of an unrevealed commitment was returned
+}
+
+/// Hash committed for an order: sha256 of the bidder account id prefixed with its length as a
+/// little endian u32, the buy amount and the sell amount as little endian u128, followed by the salt.
+/// The bidder is part of the hash, so a commitment copied by another account can never be revealed.
+pub fn hash_order(
+    bidder: &AccountId,
+    buy_amount: U128,
+    sell_amount: NearToken,
+    salt: &[u8],
+) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(4 + bidder.len() + 32 + salt.len());
+    bytes.extend_from_slice(&(bidder.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(bidder.as_bytes());
+    bytes.extend_from_slice(&buy_amount.0.to_le_bytes());
+    bytes.extend_from_slice(&sell_amount.as_yoctonear().to_le_bytes());
+    bytes.extend_from_slice(salt);
+    env::sha256(&bytes)
+}
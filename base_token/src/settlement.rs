@@ -0,0 +1,78 @@
+use crate::Price;
+use near_sdk::json_types::{U128, U64};
+use near_sdk::near;
+use std::cmp::Ordering;
+
+/// Position of an order in the sorted book: highest price first, orders with the
+/// same price keep their placement order.
+#[near(serializers = [json, borsh])]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BookKey {
+    pub price: Price,
+    pub order_id: u64,
+}
+
+impl PartialOrd for BookKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BookKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .price
+            .cmp(&self.price)
+            .then(self.order_id.cmp(&other.order_id))
+    }
+}
+
+/// Phase of the auction. Scheduled, Open and Ended follow the clock, the other ones are set by the
+/// settlement and by `cancel_auction`.
+#[near(serializers = [json, borsh])]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuctionStatus {
+    Scheduled,
+    Open,
+    Ended, // bidding is closed, sealed bids are revealed and the auction waits for its settlement
+    Settling,
+    Settled,
+    Failed,
+    Cancelled,
+}
+
+#[near(serializers = [json, borsh])]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettlementPhase {
+    Clearing, // the order book is walked from the best price to find the winning orders
+    Finished,
+}
+
+/// Progress of a settlement that can be spread over several `settle_auction_step` calls.
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct Settlement {
+    pub phase: SettlementPhase,
+    pub last_cleared: Option<BookKey>, // last order of the book checked while clearing
+    pub filled_amount: U128,           // tokens assigned to winning orders so far
+    pub clearing_price: Option<Price>, // price of the last winning order found so far
+}
+
+impl Default for Settlement {
+    fn default() -> Self {
+        Self {
+            phase: SettlementPhase::Clearing,
+            last_cleared: None,
+            filled_amount: U128(0),
+            clearing_price: None,
+        }
+    }
+}
+
+/// Progress of the `distribute` calls that pay out every order once the auction is settled.
+#[near(serializers = [json, borsh])]
+#[derive(Clone, Default)]
+pub struct Distribution {
+    pub next_winning_order: U64, // position in winning_orders of the next order to pay out
+    pub next_order: U64,         // position in orders of the next order to refund
+}
@@ -0,0 +1,60 @@
+use crate::price::mul_div;
+use near_sdk::json_types::{U128, U64};
+use near_sdk::near;
+
+/// Tokens of the creator kept by the contract and released over time. Timestamps in nanoseconds.
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct Vesting {
+    pub total: U128,
+    pub claimed: U128,
+    pub start: U64,
+    pub cliff_end: U64, // nothing is released before this timestamp
+    pub end: U64,       // everything is released from this timestamp
+}
+
+/// Creator allocation chosen at creation. The vesting starts when the auction ends.
+#[near(serializers = [json])]
+pub struct CreatorAllocation {
+    pub amount: U128,
+    pub cliff: U64,    // duration of the cliff
+    pub duration: U64, // duration of the whole vesting, cliff included
+}
+
+impl Vesting {
+    /// Tokens released at `now`, linearly between the start and the end once the cliff is over.
+    pub fn vested(&self, now: u64) -> U128 {
+        if now < self.cliff_end.0 {
+            U128(0)
+        } else if now >= self.end.0 {
+            self.total
+        } else {
+            U128(mul_div(
+                self.total.0,
+                (now - self.start.0).into(),
+                (self.end.0 - self.start.0).into(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_vested() {
+        let vesting = Vesting {
+            total: U128(1_000),
+            claimed: U128(0),
+            start: U64(100),
+            cliff_end: U64(200),
+            end: U64(500),
+        };
+
+        assert_eq!(vesting.vested(150).0, 0);
+        assert_eq!(vesting.vested(200).0, 250);
+        assert_eq!(vesting.vested(300).0, 500);
+        assert_eq!(vesting.vested(600).0, 1_000);
+    }
+}
@@ -8,8 +8,11 @@ pub struct ContractBuilder {
     total_supply: u128,
     metadata: ContractMetadata,
     image: String,
-    auction_duration: u64,
+    auction_start: Option<u64>,
+    auction_end: u64,
     min_buy_amount: u128,
+    min_raise: Option<u128>,
+    quote_token: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -32,8 +35,11 @@ impl ContractBuilder {
                 decimals: 18,
             },
             image: "https://example.com/token-image.png".to_string(),
-            auction_duration: 600, // 10 minutes
+            auction_start: None,
+            auction_end: 600,
             min_buy_amount: NearToken::from_near(1).as_yoctonear(),
+            min_raise: None,
+            quote_token: None,
         }
     }
 
@@ -55,9 +61,15 @@ impl ContractBuilder {
         self
     }
 
+    #[allow(dead_code)]
+    pub fn auction_start(mut self, auction_start: u64) -> Self {
+        self.auction_start = Some(auction_start);
+        self
+    }
+
     #[allow(clippy::missing_const_for_fn)]
-    pub fn auction_duration(mut self, auction_duration: u64) -> Self {
-        self.auction_duration = auction_duration;
+    pub fn auction_end(mut self, auction_end: u64) -> Self {
+        self.auction_end = auction_end;
         self
     }
 
@@ -67,6 +79,18 @@ impl ContractBuilder {
         self
     }
 
+    #[allow(dead_code)]
+    pub fn min_raise(mut self, min_raise: u128) -> Self {
+        self.min_raise = Some(min_raise);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn quote_token(mut self, quote_token: String) -> Self {
+        self.quote_token = Some(quote_token);
+        self
+    }
+
     pub fn build(self) -> serde_json::Value {
         serde_json::json!({
             "owner_id": self.owner_id,
@@ -78,8 +102,17 @@ impl ContractBuilder {
                 "decimals": self.metadata.decimals
             },
             "image": self.image,
-            "auction_duration": U64::from(self.auction_duration),
-            "min_buy_amount": U128::from(self.min_buy_amount)
+            "auction": {
+                "schedule": {
+                    "start": self.auction_start.map(U64::from),
+                    "end": U64::from(self.auction_end)
+                },
+                "min_buy_amount": U128::from(self.min_buy_amount),
+                "options": {
+                    "min_raise": self.min_raise.map(U128::from),
+                    "quote_token": self.quote_token
+                }
+            }
         })
     }
 }
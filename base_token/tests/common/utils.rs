@@ -1,4 +1,4 @@
-use near_sdk::json_types::U128;
+use near_sdk::json_types::{U128, U64};
 use near_sdk::Gas;
 use near_workspaces::types::NearToken;
 use serde::Deserialize;
@@ -10,6 +10,9 @@ pub const THOUSAND_NEAR: NearToken = NearToken::from_near(1000);
 
 #[derive(Debug, Deserialize)]
 pub struct Order {
+    #[allow(dead_code)]
+    pub id: String,
+
     pub bidder: String,
 
     #[allow(dead_code)]
@@ -72,6 +75,8 @@ pub async fn init_contract(
     assert!(init.is_success());
     Ok(init.total_gas_burnt)
 }
+
+#[allow(dead_code)]
 pub async fn place_order(
     account: &near_workspaces::Account,
     contract: &near_workspaces::Contract,
@@ -81,11 +86,13 @@ pub async fn place_order(
     let order_args = serde_json::json!({
         "buy_amount": U128::from(buy_amount)
     });
+    // The order storage is paid on top of the deposit so the sell amount of the order is deposit
+    let storage_cost: NearToken = contract.view("get_order_storage_cost").await?.json()?;
 
     let order = account
         .call(contract.id(), "place_order")
         .args_json(order_args)
-        .deposit(deposit)
+        .deposit(deposit.saturating_add(storage_cost))
         .transact()
         .await?;
 
@@ -103,11 +110,30 @@ pub async fn settle_auction(
     Ok(result)
 }
 
+#[allow(dead_code)]
+pub async fn settle_auction_step(
+    contract: &near_workspaces::Contract,
+    max_orders: u64,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let result = contract
+        .call("settle_auction_step")
+        .args_json(serde_json::json!({ "max_orders": U64(max_orders) }))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(result.is_success());
+    Ok(result.json::<bool>()?)
+}
+
 #[allow(dead_code)]
 pub async fn winner_list(
     contract: &near_workspaces::Contract,
 ) -> Result<near_workspaces::result::ExecutionFinalResult, Box<dyn std::error::Error>> {
-    let result = contract.call("get_auction_winner").transact().await?;
+    let result = contract
+        .call("get_auction_winner")
+        .args_json(serde_json::json!({}))
+        .transact()
+        .await?;
     assert!(result.is_success());
     Ok(result)
 }
@@ -145,6 +171,24 @@ pub async fn claim_tokens(
     Ok(result)
 }
 
+#[allow(dead_code)]
+pub async fn claim_all(
+    account: &near_workspaces::Account,
+    contract: &near_workspaces::Contract,
+) -> Result<near_workspaces::result::ExecutionFinalResult, Box<dyn std::error::Error>> {
+    let result = account.call(contract.id(), "claim_all").transact().await?;
+    Ok(result)
+}
+
+#[allow(dead_code)]
+pub async fn refund_all(
+    account: &near_workspaces::Account,
+    contract: &near_workspaces::Contract,
+) -> Result<near_workspaces::result::ExecutionFinalResult, Box<dyn std::error::Error>> {
+    let result = account.call(contract.id(), "refund_all").transact().await?;
+    Ok(result)
+}
+
 #[allow(dead_code)]
 pub async fn refund_deposit(
     account: &near_workspaces::Account,
@@ -157,6 +201,34 @@ pub async fn refund_deposit(
     Ok(result)
 }
 
+#[allow(dead_code)]
+pub async fn distribute(
+    account: &near_workspaces::Account,
+    contract: &near_workspaces::Contract,
+    batch_size: u64,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let result = account
+        .call(contract.id(), "distribute")
+        .args_json(serde_json::json!({ "batch_size": U64(batch_size) }))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(result.is_success());
+    Ok(result.json::<bool>()?)
+}
+
+#[allow(dead_code)]
+pub async fn withdraw_proceeds(
+    account: &near_workspaces::Account,
+    contract: &near_workspaces::Contract,
+) -> Result<near_workspaces::result::ExecutionFinalResult, Box<dyn std::error::Error>> {
+    let result = account
+        .call(contract.id(), "withdraw_proceeds")
+        .transact()
+        .await?;
+    Ok(result)
+}
+
 pub async fn register_token_account(
     account: &near_workspaces::Account,
     contract: &near_workspaces::Contract,
@@ -187,3 +259,39 @@ pub async fn check_balance(
 
     Ok(balance)
 }
+
+#[allow(dead_code)]
+pub async fn cancel_order(
+    account: &near_workspaces::Account,
+    contract: &near_workspaces::Contract,
+    order_id: u64,
+) -> Result<near_workspaces::result::ExecutionFinalResult, Box<dyn std::error::Error>> {
+    let result = account
+        .call(contract.id(), "cancel_order")
+        .args_json(serde_json::json!({ "order_id": U64::from(order_id) }))
+        .transact()
+        .await?;
+    Ok(result)
+}
+
+#[allow(dead_code)]
+pub async fn amend_order(
+    account: &near_workspaces::Account,
+    contract: &near_workspaces::Contract,
+    order_id: u64,
+    buy_amount: u128,
+    sell_amount: NearToken,
+    deposit: NearToken,
+) -> Result<near_workspaces::result::ExecutionFinalResult, Box<dyn std::error::Error>> {
+    let result = account
+        .call(contract.id(), "amend_order")
+        .args_json(serde_json::json!({
+            "order_id": U64::from(order_id),
+            "buy_amount": U128::from(buy_amount),
+            "sell_amount": sell_amount
+        }))
+        .deposit(deposit)
+        .transact()
+        .await?;
+    Ok(result)
+}
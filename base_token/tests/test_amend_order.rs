@@ -0,0 +1,85 @@
+use chrono::Utc;
+use near_workspaces::types::NearToken;
+
+mod common;
+
+use common::builder::ContractBuilder;
+use common::utils::{
+    amend_order, create_subaccount, init_contract, place_order, register_token_account,
+    settle_auction, winner_list, Order,
+};
+
+#[tokio::test]
+async fn test_amend_order_wins_with_raised_price() -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = near_workspaces::sandbox().await?;
+    let root = sandbox.root_account()?;
+
+    let alice = create_subaccount(&root, "alice").await?;
+    let bob = create_subaccount(&root, "bob").await?;
+    let contract_account = create_subaccount(&root, "contract").await?;
+
+    let contract_wasm = near_workspaces::compile_project("./").await?;
+    let contract = contract_account.deploy(&contract_wasm).await?.unwrap();
+
+    let now = Utc::now().timestamp();
+    let init_args = ContractBuilder::new(root.id().to_string())
+        .total_supply(10)
+        .auction_end(((now + 600) as u64) * 1000000000)
+        .min_buy_amount(NearToken::from_near(10).as_yoctonear())
+        .build();
+    init_contract(&contract, init_args).await?;
+
+    let _ = register_token_account(&alice, &contract).await?;
+    let _ = register_token_account(&bob, &contract).await?;
+
+    let _ = place_order(&alice, &contract, 10, NearToken::from_near(10)).await?;
+    let _ = place_order(&bob, &contract, 10, NearToken::from_near(20)).await?;
+
+    // Alice outbids bob by topping up her order
+    let alice_amend = amend_order(
+        &alice,
+        &contract,
+        0,
+        10,
+        NearToken::from_near(30),
+        NearToken::from_near(20),
+    )
+    .await?;
+    assert!(alice_amend.is_success());
+
+    // Orders can only be amended by their bidder
+    let bob_amend = amend_order(
+        &bob,
+        &contract,
+        0,
+        10,
+        NearToken::from_near(40),
+        NearToken::from_near(40),
+    )
+    .await?;
+    assert!(bob_amend.is_failure());
+
+    let orders = contract
+        .call("get_orders")
+        .args_json(serde_json::json!({}))
+        .transact()
+        .await?;
+    let orders_json = orders.json::<Vec<Order>>()?;
+    assert_eq!(orders_json.len(), 2);
+    assert_eq!(orders_json[0].bidder, "alice.test.near");
+    assert_eq!(
+        orders_json[0].sell_amount,
+        NearToken::from_near(30).as_yoctonear().to_string()
+    );
+
+    sandbox.fast_forward(2000).await?;
+
+    let _ = settle_auction(&contract).await?;
+
+    let winners = winner_list(&contract).await?;
+    let winners_json = winners.json::<Vec<(Order, bool)>>()?;
+    assert_eq!(winners_json.len(), 1);
+    assert_eq!(winners_json[0].0.bidder, "alice.test.near");
+
+    Ok(())
+}
@@ -1,12 +1,18 @@
-use base_token::Contract;
+use base_token::{
+    allowlist_leaf, allowlist_node, hash_order, AuctionConfig, AuctionMode, AuctionOptions,
+    AuctionSchedule, BondingCurve, Contract, CreatorAllocation, CurveShape, DutchAuction, Order,
+    PriceCurve, ProtocolFee, SealedBid, SoftClose, UnsoldSupply,
+};
+use chrono::Utc;
 use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
 use near_contract_standards::fungible_token::Balance;
 use near_contract_standards::fungible_token::FungibleTokenCore;
 use near_contract_standards::storage_management::StorageManagement;
-use near_sdk::test_utils::{accounts, VMContextBuilder};
+use near_sdk::json_types::{Base64VecU8, U128};
+use near_sdk::test_utils::{accounts, get_logs, VMContextBuilder};
 use near_sdk::testing_env;
-use near_sdk::{env, AccountId, NearToken, json_types::U64};
-use chrono::Utc;
+use near_sdk::{env, json_types::U64, AccountId, NearToken, PromiseError};
 
 const TOTAL_SUPPLY: Balance = 1_000_000_000_000_000;
 const DATA_IMAGE_SVG_NEAR_ICON: &str = "data:image/svg+xml,%3Csvg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 288 288'%3E%3Cg id='l' data-name='l'%3E%3Cpath d='M187.58,79.81l-30.1,44.69a3.2,3.2,0,0,0,4.75,4.2L191.86,103a1.2,1.2,0,0,1,2,.91v80.46a1.2,1.2,0,0,1-2.12.77L102.18,77.93A15.35,15.35,0,0,0,90.47,72.5H87.34A15.34,15.34,0,0,0,72,87.84V201.16A15.34,15.34,0,0,0,87.34,216.5h0a15.35,15.35,0,0,0,13.08-7.31l30.1-44.69a3.2,3.2,0,0,0-4.75-4.2L96.14,186a1.2,1.2,0,0,1-2-.91V104.61a1.2,1.2,0,0,1,2.12-.77l89.55,107.23a15.35,15.35,0,0,0,11.71,5.43h3.13A15.34,15.34,0,0,0,216,201.16V87.84A15.34,15.34,0,0,0,200.66,72.5h0A15.35,15.35,0,0,0,187.58,79.81Z'/%3E%3C/g%3E%3C/svg%3E";
@@ -32,6 +38,14 @@ fn new_default_meta() -> FungibleTokenMetadata {
     }
 }
 
+fn ends_at(auction_end: u64) -> AuctionSchedule {
+    AuctionSchedule {
+        start: None,
+        end: Some(U64::from(auction_end)),
+        duration: None,
+    }
+}
+
 #[test]
 fn test_new() {
     let mut context = get_context(accounts(1));
@@ -43,8 +57,11 @@ fn test_new() {
         new_default_meta(),
         DATA_IMAGE_SVG_NEAR_ICON.to_string(),
         "New cool token to be aucted".to_string(),
-        U64::from((now + 600) as u64 * 1000000000),
-        NearToken::from_near(50),
+        AuctionConfig {
+            schedule: ends_at((now + 600) as u64 * 1000000000),
+            min_buy_amount: NearToken::from_near(50),
+            options: None,
+        },
     );
 
     testing_env!(context.is_view(true).build());
@@ -74,8 +91,11 @@ fn test_transfer() {
         new_default_meta(),
         DATA_IMAGE_SVG_NEAR_ICON.to_string(),
         "New cool token to be aucted".to_string(),
-        U64::from((now + 600) as u64 * 1000000000),
-        NearToken::from_near(50),
+        AuctionConfig {
+            schedule: ends_at((now + 600) as u64 * 1000000000),
+            min_buy_amount: NearToken::from_near(50),
+            options: None,
+        },
     );
     testing_env!(context
         .storage_usage(env::storage_usage())
@@ -106,3 +126,2695 @@ fn test_transfer() {
     );
     assert_eq!(contract.ft_balance_of(accounts(1)).0, transfer_amount);
 }
+
+// Auction that ends at block timestamp 1_000
+fn init_auction(owner_id: AccountId, total_supply: Balance, min_buy_amount: NearToken) -> Contract {
+    Contract::new(
+        owner_id,
+        total_supply.into(),
+        new_default_meta(),
+        DATA_IMAGE_SVG_NEAR_ICON.to_string(),
+        "New cool token to be aucted".to_string(),
+        AuctionConfig {
+            schedule: ends_at(1_000),
+            min_buy_amount,
+            options: None,
+        },
+    )
+}
+
+fn register_bidder(contract: &mut Contract, context: &mut VMContextBuilder, bidder: AccountId) {
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(contract.storage_balance_bounds().min)
+        .predecessor_account_id(bidder)
+        .build());
+    contract.storage_deposit(None, None);
+}
+
+fn register_and_place_order(
+    contract: &mut Contract,
+    context: &mut VMContextBuilder,
+    bidder: AccountId,
+    buy_amount: Balance,
+    sell_amount: NearToken,
+) {
+    register_bidder(contract, context, bidder.clone());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(sell_amount.saturating_add(contract.get_order_storage_cost()))
+        .predecessor_account_id(bidder)
+        .build());
+    contract.place_order(buy_amount.into());
+}
+
+#[test]
+fn test_settle_ranks_bids_that_f64_cannot_tell_apart() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let one_near = NearToken::from_near(1).as_yoctonear();
+    let mut contract = Contract::new(
+        env::current_account_id(),
+        one_near.into(),
+        new_default_meta(),
+        DATA_IMAGE_SVG_NEAR_ICON.to_string(),
+        "New cool token to be aucted".to_string(),
+        AuctionConfig {
+            schedule: ends_at(1_000),
+            min_buy_amount: NearToken::from_near(1),
+            options: None,
+        },
+    );
+
+    // Both bids ask for the whole supply and their prices are equal as f64
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        one_near,
+        NearToken::from_near(10),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        one_near,
+        NearToken::from_yoctonear(10 * one_near + 1),
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .block_timestamp(2_000)
+        .build());
+    contract.settle_auction();
+
+    let winners = contract.get_auction_winner(None, None);
+    assert_eq!(winners.len(), 1);
+    assert_eq!(
+        serde_json::to_value(&winners[0].0).unwrap()["bidder"],
+        accounts(3).to_string()
+    );
+}
+
+#[test]
+fn test_place_order_registers_new_bidder() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 10, NearToken::from_near(10));
+    let storage_cost = contract
+        .storage_balance_bounds()
+        .min
+        .saturating_add(contract.get_order_storage_cost());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(20).saturating_add(storage_cost))
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.place_order(10.into());
+
+    assert!(contract.storage_balance_of(accounts(2)).is_some());
+    let order = serde_json::to_value(contract.get_order(U64::from(0))).unwrap();
+    assert_eq!(
+        order["sell_amount"],
+        NearToken::from_near(20).as_yoctonear().to_string()
+    );
+}
+
+#[test]
+#[should_panic(expected = "The attached deposit must cover the storage cost")]
+fn test_place_order_deposit_below_storage_cost() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 10, NearToken::from_near(10));
+    let storage_cost = contract.storage_balance_bounds().min;
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(storage_cost)
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.place_order(10.into());
+}
+
+#[test]
+fn test_order_storage_cost_covers_the_order() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 1_000, NearToken::from_near(1));
+
+    for index in 0..20u32 {
+        let bidder: AccountId = format!("{}{}.near", "b".repeat(57), index % 4)
+            .parse()
+            .unwrap();
+        let storage_usage = env::storage_usage();
+        register_and_place_order(
+            &mut contract,
+            &mut context,
+            bidder,
+            1,
+            NearToken::from_near(1 + index as u128 % 7),
+        );
+        let order_storage = (env::storage_usage() - storage_usage) as u128;
+        let registration_storage = if index < 4 {
+            contract.storage_balance_bounds().min.as_yoctonear()
+                / env::storage_byte_cost().as_yoctonear()
+        } else {
+            0
+        };
+        assert!(
+            env::storage_byte_cost().saturating_mul(order_storage - registration_storage)
+                <= contract.get_order_storage_cost()
+        );
+    }
+}
+
+#[test]
+fn test_settle_keeps_placement_order_for_equal_prices() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = Contract::new(
+        env::current_account_id(),
+        10.into(),
+        new_default_meta(),
+        DATA_IMAGE_SVG_NEAR_ICON.to_string(),
+        "New cool token to be aucted".to_string(),
+        AuctionConfig {
+            schedule: ends_at(1_000),
+            min_buy_amount: NearToken::from_near(10),
+            options: None,
+        },
+    );
+
+    // 2 NEAR per token for every bid, written with different fractions
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        4,
+        NearToken::from_near(8),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        3,
+        NearToken::from_near(6),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(4),
+        6,
+        NearToken::from_near(12),
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .block_timestamp(2_000)
+        .build());
+    contract.settle_auction();
+
+    let bidders: Vec<String> = contract
+        .get_auction_winner(None, None)
+        .iter()
+        .map(|(order, _)| {
+            serde_json::to_value(order).unwrap()["bidder"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(
+        bidders,
+        vec![
+            accounts(2).to_string(),
+            accounts(3).to_string(),
+            accounts(4).to_string()
+        ]
+    );
+    assert_eq!(contract.get_final_auction_price(), NearToken::from_near(2));
+}
+
+#[test]
+fn test_place_order_at_reserve_price() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = Contract::new(
+        env::current_account_id(),
+        TOTAL_SUPPLY.into(),
+        new_default_meta(),
+        DATA_IMAGE_SVG_NEAR_ICON.to_string(),
+        "New cool token to be aucted".to_string(),
+        AuctionConfig {
+            schedule: ends_at(1_000),
+            min_buy_amount: NearToken::from_near(50),
+            options: None,
+        },
+    );
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        TOTAL_SUPPLY,
+        NearToken::from_near(50),
+    );
+    assert_eq!(contract.get_orders(None, None).len(), 1);
+}
+
+#[test]
+#[should_panic(
+    expected = "Offer price is less than minimum price that auctioner is willing to accept"
+)]
+fn test_place_order_one_yocto_below_reserve_price() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = Contract::new(
+        env::current_account_id(),
+        TOTAL_SUPPLY.into(),
+        new_default_meta(),
+        DATA_IMAGE_SVG_NEAR_ICON.to_string(),
+        "New cool token to be aucted".to_string(),
+        AuctionConfig {
+            schedule: ends_at(1_000),
+            min_buy_amount: NearToken::from_near(50),
+            options: None,
+        },
+    );
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        TOTAL_SUPPLY,
+        NearToken::from_near(50).saturating_sub(NearToken::from_yoctonear(1)),
+    );
+}
+
+#[test]
+fn test_cancel_order_keeps_placement_order() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 10, NearToken::from_near(10));
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        1,
+        NearToken::from_near(2),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        1,
+        NearToken::from_near(3),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(4),
+        1,
+        NearToken::from_near(4),
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .predecessor_account_id(accounts(3))
+        .build());
+    contract.cancel_order(U64::from(1));
+
+    let bidders = |orders: Vec<Order>| -> Vec<String> {
+        orders
+            .iter()
+            .map(|order| {
+                serde_json::to_value(order).unwrap()["bidder"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect()
+    };
+    assert_eq!(
+        bidders(contract.get_orders(None, None)),
+        vec![accounts(2).to_string(), accounts(4).to_string()]
+    );
+    assert_eq!(
+        bidders(contract.get_orders(Some(U64(2)), None)),
+        vec![accounts(4).to_string()]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Only the bidder can cancel the order")]
+fn test_cancel_order_of_another_bidder() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 10, NearToken::from_near(10));
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        1,
+        NearToken::from_near(2),
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .predecessor_account_id(accounts(3))
+        .build());
+    contract.cancel_order(U64::from(0));
+}
+
+#[test]
+#[should_panic(expected = "Orders can not be cancelled this close to the end of the auction")]
+fn test_cancel_order_after_cancellation_cutoff() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(accounts(1), 10, NearToken::from_near(10));
+    contract.set_cancellation_cutoff(Some(U64::from(300)));
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        1,
+        NearToken::from_near(2),
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .predecessor_account_id(accounts(2))
+        .block_timestamp(800)
+        .build());
+    contract.cancel_order(U64::from(0));
+}
+
+#[test]
+fn test_amend_order_keeps_order_position() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 10, NearToken::from_near(10));
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        2,
+        NearToken::from_near(4),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        2,
+        NearToken::from_near(6),
+    );
+
+    // Raise the price from 2 to 3 NEAR per token and the size from 2 to 4 tokens
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(8))
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.amend_order(U64::from(0), 4.into(), NearToken::from_near(12));
+
+    let orders = serde_json::to_value(contract.get_orders(None, None)).unwrap();
+    assert_eq!(orders[0]["bidder"], accounts(2).to_string());
+    assert_eq!(orders[0]["buy_amount"], "4");
+    assert_eq!(
+        orders[0]["sell_amount"],
+        NearToken::from_near(12).as_yoctonear().to_string()
+    );
+    assert_eq!(orders[1]["bidder"], accounts(3).to_string());
+}
+
+#[test]
+#[should_panic(expected = "Amended price can not be lower than the current price")]
+fn test_amend_order_with_lower_price() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 10, NearToken::from_near(10));
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        2,
+        NearToken::from_near(6),
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.amend_order(U64::from(0), 2.into(), NearToken::from_near(4));
+}
+
+#[test]
+#[should_panic(expected = "The attached deposit is too small")]
+fn test_amend_order_without_top_up() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 10, NearToken::from_near(10));
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        2,
+        NearToken::from_near(4),
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(1))
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.amend_order(U64::from(0), 2.into(), NearToken::from_near(6));
+}
+
+#[test]
+fn test_claim_all_and_refund_all_settle_every_order_of_a_bidder() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 10, NearToken::from_near(10));
+
+    // accounts(2) wins with two orders and loses with a third one
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        4,
+        NearToken::from_near(16),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        4,
+        NearToken::from_near(12),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        2,
+        NearToken::from_near(6),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        2,
+        NearToken::from_near(4),
+    );
+
+    let order = serde_json::to_value(contract.get_order(U64::from(3))).unwrap();
+    assert_eq!(order["id"], "3");
+    assert_eq!(order["buy_amount"], "2");
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .block_timestamp(2_000)
+        .build());
+    contract.settle_auction();
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.claim_all();
+    assert_eq!(contract.ft_balance_of(accounts(2)).0, 8);
+
+    contract.refund_all();
+    assert_eq!(contract.get_refunded_orders(None, None).len(), 1);
+}
+
+#[test]
+fn test_distribute_in_batches() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 10, NearToken::from_near(10));
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        4,
+        NearToken::from_near(16),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        4,
+        NearToken::from_near(12),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(4),
+        2,
+        NearToken::from_near(6),
+    );
+    // Below the clearing price, refunded by distribute
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        2,
+        NearToken::from_near(4),
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .block_timestamp(2_000)
+        .build());
+    contract.settle_auction();
+
+    // accounts(3) claims on its own, distribute must not pay it twice
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .predecessor_account_id(accounts(3))
+        .build());
+    contract.claim_all();
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .predecessor_account_id(accounts(5))
+        .build());
+    assert!(!contract.distribute(U64(2)));
+    assert_eq!(contract.ft_balance_of(accounts(2)).0, 4);
+    assert_eq!(contract.ft_balance_of(accounts(4)).0, 0);
+
+    assert!(contract.distribute(U64(10)));
+    assert_eq!(contract.ft_balance_of(accounts(2)).0, 4);
+    assert_eq!(contract.ft_balance_of(accounts(3)).0, 4);
+    assert_eq!(contract.ft_balance_of(accounts(4)).0, 2);
+
+    let refunded = serde_json::to_value(contract.get_refunded_orders(None, None)).unwrap();
+    assert_eq!(refunded.as_array().unwrap().len(), 1);
+    assert_eq!(refunded[0]["id"], "3");
+
+    let distribution = serde_json::to_value(contract.get_distribution()).unwrap();
+    assert_eq!(distribution["next_winning_order"], "3");
+    assert_eq!(distribution["next_order"], "4");
+}
+
+#[test]
+#[should_panic(expected = "Auction not settled yet")]
+fn test_distribute_before_settlement() {
+    let context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 10, NearToken::from_near(10));
+
+    contract.distribute(U64(10));
+}
+
+#[test]
+fn test_claim_and_refund_on_behalf_of_bidders() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 10, NearToken::from_near(10));
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        10,
+        NearToken::from_near(20),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        2,
+        NearToken::from_near(2),
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .block_timestamp(2_000)
+        .build());
+    contract.settle_auction();
+
+    // A keeper triggers both payouts, nothing is paid to the keeper
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .predecessor_account_id(accounts(5))
+        .build());
+    contract.claim_tokens_for(accounts(2));
+    contract.refund_deposit_for(accounts(3));
+
+    assert_eq!(contract.ft_balance_of(accounts(2)).0, 10);
+    assert_eq!(contract.ft_balance_of(accounts(5)).0, 0);
+    let refunded = serde_json::to_value(contract.get_refunded_orders(None, None)).unwrap();
+    assert_eq!(refunded[0]["bidder"], accounts(3).to_string());
+}
+
+#[test]
+#[should_panic(expected = "You are not allowed to claim or have already claimed")]
+fn test_claim_tokens_for_account_without_winning_order() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 10, NearToken::from_near(10));
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        10,
+        NearToken::from_near(20),
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .block_timestamp(2_000)
+        .build());
+    contract.settle_auction();
+
+    contract.claim_tokens_for(accounts(4));
+}
+
+#[test]
+#[should_panic(expected = "No refundable order found for this account")]
+fn test_refund_all_twice() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 10, NearToken::from_near(10));
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        10,
+        NearToken::from_near(20),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        5,
+        NearToken::from_near(5),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        5,
+        NearToken::from_near(5),
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .block_timestamp(2_000)
+        .build());
+    contract.settle_auction();
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .predecessor_account_id(accounts(3))
+        .build());
+    contract.refund_all();
+    contract.refund_all();
+}
+
+#[test]
+fn test_paginated_and_filtered_views() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 10, NearToken::from_near(10));
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        2,
+        NearToken::from_near(4),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        2,
+        NearToken::from_near(6),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        2,
+        NearToken::from_near(8),
+    );
+
+    let page =
+        serde_json::to_value(contract.get_orders(Some(U64::from(1)), Some(U64::from(1)))).unwrap();
+    assert_eq!(page.as_array().unwrap().len(), 1);
+    assert_eq!(page[0]["id"], "1");
+    assert!(contract.get_orders(Some(U64::from(5)), None).is_empty());
+
+    let bidder_orders = serde_json::to_value(contract.get_orders_by_bidder(accounts(2))).unwrap();
+    assert_eq!(bidder_orders.as_array().unwrap().len(), 2);
+    assert_eq!(bidder_orders[0]["id"], "0");
+    assert_eq!(bidder_orders[1]["id"], "2");
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .block_timestamp(2_000)
+        .build());
+    contract.settle_auction();
+
+    assert_eq!(
+        contract
+            .get_auction_winner(Some(U64::from(2)), Some(U64::from(10)))
+            .len(),
+        1
+    );
+
+    let summary = serde_json::to_value(contract.get_auction_summary()).unwrap();
+    assert_eq!(summary["total_orders"], "3");
+    assert_eq!(summary["total_winning_orders"], "3");
+    assert_eq!(summary["is_settled"], true);
+    assert!(summary.get("winning_orders").is_none());
+}
+
+#[test]
+fn test_settle_auction_in_steps() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 5, NearToken::from_near(5));
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        2,
+        NearToken::from_near(4),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        2,
+        NearToken::from_near(6),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(4),
+        2,
+        NearToken::from_near(8),
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .block_timestamp(2_000)
+        .build());
+
+    // One winning order per call
+    assert!(!contract.settle_auction_step(U64::from(1)));
+    assert!(!contract.settle_auction_step(U64::from(1)));
+    let settlement = serde_json::to_value(contract.get_settlement()).unwrap();
+    assert_eq!(settlement["phase"], "Clearing");
+    assert_eq!(settlement["filled_amount"], "4");
+    assert!(contract.settle_auction_step(U64::from(1)));
+
+    let settlement = serde_json::to_value(contract.get_settlement()).unwrap();
+    assert_eq!(settlement["phase"], "Finished");
+    assert_eq!(settlement["filled_amount"], "5");
+
+    let winners = serde_json::to_value(contract.get_auction_winner(None, None)).unwrap();
+    assert_eq!(winners.as_array().unwrap().len(), 3);
+    assert_eq!(winners[0][0]["bidder"], accounts(4).to_string());
+    assert_eq!(winners[2][0]["bidder"], accounts(2).to_string());
+    assert_eq!(winners[2][0]["buy_amount"], "1");
+    assert_eq!(contract.get_final_auction_price(), NearToken::from_near(2));
+}
+
+#[test]
+#[should_panic(expected = "Auction already settled")]
+fn test_settle_auction_step_after_settlement() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 5, NearToken::from_near(5));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .block_timestamp(2_000)
+        .build());
+    assert!(contract.settle_auction_step(U64::from(10)));
+    contract.settle_auction_step(U64::from(10));
+}
+
+#[test]
+fn test_order_book_is_sorted_as_orders_are_placed() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction(env::current_account_id(), 5, NearToken::from_near(5));
+    assert!(contract.get_current_clearing_price().is_none());
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        2,
+        NearToken::from_near(4),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        2,
+        NearToken::from_near(8),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(4),
+        2,
+        NearToken::from_near(6),
+    );
+
+    let book = serde_json::to_value(contract.get_order_book(None, None)).unwrap();
+    assert_eq!(book[0]["id"], "1");
+    assert_eq!(book[1]["id"], "2");
+    assert_eq!(book[2]["id"], "0");
+
+    // 4 tokens at 4 and 3 NEAR, the fifth one clears at 2 NEAR
+    let price = contract.get_current_clearing_price().unwrap();
+    assert_eq!(price.per_token(), NearToken::from_near(2));
+
+    // Raising the lowest bid moves it to the top of the book
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(6))
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.amend_order(U64::from(0), 2.into(), NearToken::from_near(10));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .predecessor_account_id(accounts(4))
+        .build());
+    contract.cancel_order(U64::from(2));
+
+    let book = serde_json::to_value(contract.get_order_book(None, None)).unwrap();
+    assert_eq!(book.as_array().unwrap().len(), 2);
+    assert_eq!(book[0]["id"], "0");
+    assert_eq!(book[1]["id"], "1");
+    assert_eq!(
+        serde_json::to_value(contract.get_order_book(Some(U64::from(1)), Some(U64::from(1))))
+            .unwrap()[0]["id"],
+        "1"
+    );
+
+    let price = contract.get_current_clearing_price().unwrap();
+    assert_eq!(price.per_token(), NearToken::from_near(4));
+}
+
+#[test]
+fn test_auction_schedule_with_start_and_duration() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.block_timestamp(100).build());
+    let contract = Contract::new(
+        env::current_account_id(),
+        10.into(),
+        new_default_meta(),
+        DATA_IMAGE_SVG_NEAR_ICON.to_string(),
+        "New cool token to be aucted".to_string(),
+        AuctionConfig {
+            schedule: AuctionSchedule {
+                start: Some(U64::from(500)),
+                end: None,
+                duration: Some(U64::from(1_000)),
+            },
+            min_buy_amount: NearToken::from_near(10),
+            options: None,
+        },
+    );
+
+    let countdown = serde_json::to_value(contract.get_auction_countdown()).unwrap();
+    assert_eq!(countdown["auction_start"], "500");
+    assert_eq!(countdown["auction_end"], "1500");
+    assert_eq!(countdown["starts_in"], "400");
+    assert_eq!(countdown["ends_in"], "1400");
+
+    testing_env!(context.block_timestamp(2_000).build());
+    let countdown = serde_json::to_value(contract.get_auction_countdown()).unwrap();
+    assert_eq!(countdown["starts_in"], "0");
+    assert_eq!(countdown["ends_in"], "0");
+}
+
+#[test]
+#[should_panic(expected = "Either the auction end or the auction duration must be set")]
+fn test_auction_schedule_with_end_and_duration() {
+    let context = get_context(accounts(1));
+    testing_env!(context.build());
+    Contract::new(
+        env::current_account_id(),
+        10.into(),
+        new_default_meta(),
+        DATA_IMAGE_SVG_NEAR_ICON.to_string(),
+        "New cool token to be aucted".to_string(),
+        AuctionConfig {
+            schedule: AuctionSchedule {
+                start: None,
+                end: Some(U64::from(1_000)),
+                duration: Some(U64::from(1_000)),
+            },
+            min_buy_amount: NearToken::from_near(10),
+            options: None,
+        },
+    );
+}
+
+#[test]
+#[should_panic(expected = "Auction has not started yet")]
+fn test_place_order_before_auction_start() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = Contract::new(
+        env::current_account_id(),
+        10.into(),
+        new_default_meta(),
+        DATA_IMAGE_SVG_NEAR_ICON.to_string(),
+        "New cool token to be aucted".to_string(),
+        AuctionConfig {
+            schedule: AuctionSchedule {
+                start: Some(U64::from(500)),
+                end: Some(U64::from(1_000)),
+                duration: None,
+            },
+            min_buy_amount: NearToken::from_near(10),
+            options: None,
+        },
+    );
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        1,
+        NearToken::from_near(2),
+    );
+}
+
+fn init_auction_with_options(owner_id: AccountId, options: AuctionOptions) -> Contract {
+    Contract::new(
+        owner_id,
+        10.into(),
+        new_default_meta(),
+        DATA_IMAGE_SVG_NEAR_ICON.to_string(),
+        "New cool token to be aucted".to_string(),
+        AuctionConfig {
+            schedule: ends_at(1_000),
+            min_buy_amount: NearToken::from_near(10),
+            options: Some(options),
+        },
+    )
+}
+
+fn place_orders_and_settle(contract: &mut Contract, context: &mut VMContextBuilder) {
+    register_and_place_order(contract, context, accounts(2), 1, NearToken::from_near(2));
+    register_and_place_order(contract, context, accounts(3), 2, NearToken::from_near(5));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .block_timestamp(2_000)
+        .build());
+    contract.settle_auction();
+}
+
+#[test]
+fn test_auction_fails_below_min_raise() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            min_raise: Some(NearToken::from_near(20)),
+            ..Default::default()
+        },
+    );
+
+    place_orders_and_settle(&mut contract, &mut context);
+
+    let summary = serde_json::to_value(contract.get_auction_summary()).unwrap();
+    assert_eq!(summary["is_settled"], true);
+    assert_eq!(summary["is_failed"], true);
+    assert_eq!(
+        summary["total_bids"],
+        NearToken::from_near(7).as_yoctonear().to_string()
+    );
+    assert!(contract.get_auction_winner(None, None).is_empty());
+    assert_eq!(contract.ft_balance_of(accounts(1)).0, 10);
+    assert_eq!(contract.ft_balance_of(accounts(0)).0, 0);
+
+    // Every order is refunded in full
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .predecessor_account_id(accounts(3))
+        .build());
+    contract.refund_all();
+
+    let refunded = serde_json::to_value(contract.get_refunded_orders(None, None)).unwrap();
+    assert_eq!(
+        refunded[0]["sell_amount"],
+        NearToken::from_near(5).as_yoctonear().to_string()
+    );
+}
+
+#[test]
+fn test_failed_auction_burns_the_supply() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            min_raise: Some(NearToken::from_near(20)),
+            unsold_supply: Some(UnsoldSupply::Burn),
+            ..Default::default()
+        },
+    );
+
+    place_orders_and_settle(&mut contract, &mut context);
+
+    assert_eq!(contract.ft_total_supply().0, 0);
+    assert_eq!(contract.ft_balance_of(accounts(1)).0, 0);
+}
+
+#[test]
+fn test_auction_settles_when_min_raise_is_reached() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            min_raise: Some(NearToken::from_near(7)),
+            ..Default::default()
+        },
+    );
+
+    place_orders_and_settle(&mut contract, &mut context);
+
+    let summary = serde_json::to_value(contract.get_auction_summary()).unwrap();
+    assert_eq!(summary["is_failed"], false);
+    assert_eq!(contract.get_auction_winner(None, None).len(), 2);
+}
+
+fn settle_undersubscribed_auction(unsold_supply: UnsoldSupply) -> Contract {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            unsold_supply: Some(unsold_supply),
+            ..Default::default()
+        },
+    );
+
+    // 3 of the 10 tokens are sold
+    place_orders_and_settle(&mut contract, &mut context);
+    assert_eq!(contract.get_unsold_amount().0, 7);
+
+    contract
+}
+
+#[test]
+fn test_unsold_supply_is_returned_to_auctioner() {
+    let contract = settle_undersubscribed_auction(UnsoldSupply::ReturnToAuctioner);
+
+    assert_eq!(contract.ft_balance_of(accounts(1)).0, 7);
+    assert_eq!(contract.ft_balance_of(accounts(0)).0, 3);
+    assert_eq!(contract.ft_total_supply().0, 10);
+}
+
+#[test]
+fn test_unsold_supply_is_burned() {
+    let contract = settle_undersubscribed_auction(UnsoldSupply::Burn);
+
+    assert_eq!(contract.ft_balance_of(accounts(1)).0, 0);
+    assert_eq!(contract.ft_balance_of(accounts(0)).0, 3);
+    assert_eq!(contract.ft_total_supply().0, 3);
+}
+
+#[test]
+fn test_unsold_supply_is_kept_in_treasury() {
+    let contract = settle_undersubscribed_auction(UnsoldSupply::KeepInTreasury);
+
+    assert_eq!(contract.ft_balance_of(accounts(1)).0, 0);
+    assert_eq!(contract.ft_balance_of(accounts(0)).0, 10);
+
+    let summary = serde_json::to_value(contract.get_auction_summary()).unwrap();
+    assert_eq!(summary["unsold_supply"], "KeepInTreasury");
+    assert_eq!(summary["unsold_amount"], "7");
+}
+
+#[test]
+fn test_withdraw_proceeds() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(accounts(1), AuctionOptions::default());
+
+    // 3 tokens sold at 2 NEAR
+    place_orders_and_settle(&mut contract, &mut context);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.withdraw_proceeds();
+
+    let summary = serde_json::to_value(contract.get_auction_summary()).unwrap();
+    assert_eq!(
+        summary["total_proceeds"],
+        NearToken::from_near(6).as_yoctonear().to_string()
+    );
+    assert_eq!(summary["withdrawn_proceeds"], summary["total_proceeds"]);
+}
+
+#[test]
+#[should_panic(expected = "No proceeds to withdraw")]
+fn test_withdraw_proceeds_twice() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(accounts(1), AuctionOptions::default());
+
+    place_orders_and_settle(&mut contract, &mut context);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.withdraw_proceeds();
+    contract.withdraw_proceeds();
+}
+
+#[test]
+#[should_panic(expected = "Only the auctioner can withdraw the proceeds")]
+fn test_withdraw_proceeds_by_bidder() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(accounts(1), AuctionOptions::default());
+
+    place_orders_and_settle(&mut contract, &mut context);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.withdraw_proceeds();
+}
+
+#[test]
+fn test_protocol_fee_is_taken_from_proceeds() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            protocol_fee: Some(ProtocolFee {
+                fee_bps: 250,
+                recipient: accounts(5),
+            }),
+            ..Default::default()
+        },
+    );
+
+    // 6 NEAR of proceeds, 2.5% of them go to the fee recipient
+    place_orders_and_settle(&mut contract, &mut context);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .predecessor_account_id(accounts(1))
+        .build());
+    contract.withdraw_proceeds();
+
+    let summary = serde_json::to_value(contract.get_auction_summary()).unwrap();
+    assert_eq!(summary["protocol_fee"]["fee_bps"], 250);
+    assert_eq!(
+        summary["fee_amount"],
+        NearToken::from_millinear(150).as_yoctonear().to_string()
+    );
+    assert_eq!(
+        summary["withdrawn_proceeds"],
+        NearToken::from_millinear(5_850).as_yoctonear().to_string()
+    );
+}
+
+#[test]
+#[should_panic(expected = "Protocol fee can not be more than 10000 basis points")]
+fn test_protocol_fee_above_maximum() {
+    let context = get_context(accounts(1));
+    testing_env!(context.build());
+    init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            protocol_fee: Some(ProtocolFee {
+                fee_bps: 10_001,
+                recipient: accounts(5),
+            }),
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_bid_in_quote_token() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            quote_token: Some(accounts(5)),
+            ..Default::default()
+        },
+    );
+    register_bidder(&mut contract, &mut context, accounts(2));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_yoctonear(0))
+        .predecessor_account_id(accounts(5))
+        .build());
+    contract.ft_on_transfer(
+        accounts(2),
+        U128(NearToken::from_near(20).as_yoctonear()),
+        r#"{"buy_amount": "10"}"#.to_string(),
+    );
+
+    let order = serde_json::to_value(contract.get_order(U64::from(0))).unwrap();
+    assert_eq!(order["bidder"], accounts(2).to_string());
+    assert_eq!(order["buy_amount"], "10");
+    assert_eq!(
+        order["sell_amount"],
+        NearToken::from_near(20).as_yoctonear().to_string()
+    );
+    let summary = serde_json::to_value(contract.get_auction_summary()).unwrap();
+    assert_eq!(summary["quote_token"], accounts(5).to_string());
+}
+
+#[test]
+#[should_panic(expected = "Bids are only accepted in the quote token of the auction")]
+fn test_bid_in_another_token() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            quote_token: Some(accounts(5)),
+            ..Default::default()
+        },
+    );
+    register_bidder(&mut contract, &mut context, accounts(2));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_yoctonear(0))
+        .predecessor_account_id(accounts(4))
+        .build());
+    contract.ft_on_transfer(
+        accounts(2),
+        U128(NearToken::from_near(20).as_yoctonear()),
+        r#"{"buy_amount": "10"}"#.to_string(),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Bids are placed in")]
+fn test_place_order_with_near_in_quote_token_auction() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            quote_token: Some(accounts(5)),
+            ..Default::default()
+        },
+    );
+    register_bidder(&mut contract, &mut context, accounts(2));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(20))
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.place_order(10.into());
+}
+
+#[test]
+fn test_failed_quote_transfer_is_kept_for_receiver() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            quote_token: Some(accounts(5)),
+            ..Default::default()
+        },
+    );
+    register_bidder(&mut contract, &mut context, accounts(2));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_yoctonear(0))
+        .predecessor_account_id(accounts(0))
+        .build());
+    contract.resolve_quote_transfer(
+        accounts(2),
+        NearToken::from_near(3),
+        Err(PromiseError::Failed),
+    );
+    assert_eq!(
+        contract.get_unpaid_transfer(accounts(2)),
+        NearToken::from_near(3)
+    );
+
+    contract.retry_unpaid_transfer(accounts(2));
+    assert_eq!(
+        contract.get_unpaid_transfer(accounts(2)),
+        NearToken::from_yoctonear(0)
+    );
+}
+
+fn register_and_commit_order(
+    contract: &mut Contract,
+    context: &mut VMContextBuilder,
+    bidder: AccountId,
+    buy_amount: u128,
+    sell_amount: NearToken,
+    deposit: NearToken,
+) -> U64 {
+    register_bidder(contract, context, bidder.clone());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(deposit)
+        .predecessor_account_id(bidder.clone())
+        .build());
+    let salt = bidder.as_bytes();
+    contract.commit_order(Base64VecU8(hash_order(
+        &bidder,
+        U128(buy_amount),
+        sell_amount,
+        salt,
+    )))
+}
+
+#[test]
+fn test_commit_order_registers_new_bidder() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    // Reveals end at 1_500, 10% of the deposit of an unrevealed commitment is kept
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            sealed_bid: Some(SealedBid {
+                reveal_duration: U64(500),
+                penalty_bps: 1_000,
+            }),
+            ..Default::default()
+        },
+    );
+    let storage_cost = contract.storage_balance_bounds().min;
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(25).saturating_add(storage_cost))
+        .predecessor_account_id(accounts(2))
+        .build());
+    let hash = hash_order(&accounts(2), U128(10), NearToken::from_near(20), b"salt");
+    contract.commit_order(Base64VecU8(hash));
+
+    assert!(contract.storage_balance_of(accounts(2)).is_some());
+    let commitments = serde_json::to_value(contract.get_commitments(None, None)).unwrap();
+    assert_eq!(
+        commitments[0]["deposit"],
+        NearToken::from_near(25).as_yoctonear().to_string()
+    );
+}
+
+#[test]
+fn test_sealed_bid_commit_and_reveal() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            sealed_bid: Some(SealedBid {
+                reveal_duration: U64(500),
+                penalty_bps: 1_000,
+            }),
+            ..Default::default()
+        },
+    );
+
+    let revealed = register_and_commit_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        10,
+        NearToken::from_near(20),
+        NearToken::from_near(25),
+    );
+    let unrevealed = register_and_commit_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        10,
+        NearToken::from_near(30),
+        NearToken::from_near(30),
+    );
+    // Nothing of the bids is public while bidding is open
+    assert!(contract.get_orders(None, None).is_empty());
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_yoctonear(0))
+        .predecessor_account_id(accounts(2))
+        .block_timestamp(1_200)
+        .build());
+    contract.reveal_order(
+        revealed,
+        U128(10),
+        NearToken::from_near(20),
+        Base64VecU8(accounts(2).as_bytes().to_vec()),
+    );
+    assert_eq!(contract.get_orders(None, None).len(), 1);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .predecessor_account_id(accounts(5))
+        .block_timestamp(2_000)
+        .build());
+    contract.settle_auction();
+    contract.refund_commitment(unrevealed);
+
+    let winners = serde_json::to_value(contract.get_auction_winner(None, None)).unwrap();
+    assert_eq!(winners[0][0]["bidder"], accounts(2).to_string());
+    let commitments = serde_json::to_value(contract.get_commitments(None, None)).unwrap();
+    assert_eq!(commitments[0]["revealed"], true);
+    assert_eq!(commitments[1]["revealed"], false);
+    assert_eq!(commitments[1]["refunded"], true);
+}
+
+#[test]
+#[should_panic(expected = "Revealed order does not match the commitment")]
+fn test_sealed_bid_reveal_with_another_order() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            sealed_bid: Some(SealedBid {
+                reveal_duration: U64(500),
+                penalty_bps: 1_000,
+            }),
+            ..Default::default()
+        },
+    );
+
+    let commitment = register_and_commit_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        10,
+        NearToken::from_near(20),
+        NearToken::from_near(25),
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_yoctonear(0))
+        .block_timestamp(1_200)
+        .build());
+    contract.reveal_order(
+        commitment,
+        U128(10),
+        NearToken::from_near(25),
+        Base64VecU8(accounts(2).as_bytes().to_vec()),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Revealed order does not match the commitment")]
+fn test_sealed_bid_reveal_copied_commitment() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            sealed_bid: Some(SealedBid {
+                reveal_duration: U64(500),
+                penalty_bps: 1_000,
+            }),
+            ..Default::default()
+        },
+    );
+
+    register_and_commit_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        10,
+        NearToken::from_near(20),
+        NearToken::from_near(25),
+    );
+    // Another bidder commits the same hash to reveal the order behind it at a price it now knows
+    let hash = contract.get_commitments(None, None)[0].hash.clone();
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(
+            NearToken::from_near(25).saturating_add(contract.storage_balance_bounds().min)
+        )
+        .predecessor_account_id(accounts(3))
+        .build());
+    let copy = contract.commit_order(hash);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_yoctonear(0))
+        .predecessor_account_id(accounts(3))
+        .block_timestamp(1_200)
+        .build());
+    contract.reveal_order(
+        copy,
+        U128(10),
+        NearToken::from_near(20),
+        Base64VecU8(accounts(2).as_bytes().to_vec()),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Orders can only be revealed during the reveal period")]
+fn test_sealed_bid_reveal_while_bidding_is_open() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            sealed_bid: Some(SealedBid {
+                reveal_duration: U64(500),
+                penalty_bps: 1_000,
+            }),
+            ..Default::default()
+        },
+    );
+
+    let commitment = register_and_commit_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        10,
+        NearToken::from_near(20),
+        NearToken::from_near(25),
+    );
+
+    contract.reveal_order(
+        commitment,
+        U128(10),
+        NearToken::from_near(20),
+        Base64VecU8(accounts(2).as_bytes().to_vec()),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Reveal period has not ended yet")]
+fn test_settle_sealed_bid_auction_during_reveal_period() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            sealed_bid: Some(SealedBid {
+                reveal_duration: U64(500),
+                penalty_bps: 1_000,
+            }),
+            ..Default::default()
+        },
+    );
+
+    testing_env!(context.block_timestamp(1_200).build());
+    contract.settle_auction();
+}
+
+#[test]
+fn test_dutch_auction_settles_when_sold_out() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    // 10 NEAR per token when bidding opens, down to the reserve of 1 NEAR per token at 1_000
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            mode: Some(AuctionMode::Dutch(DutchAuction {
+                start_buy_amount: NearToken::from_near(100),
+                curve: PriceCurve::Linear,
+            })),
+            ..Default::default()
+        },
+    );
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        4,
+        NearToken::from_near(40),
+    );
+    assert_eq!(
+        serde_json::to_value(contract.get_auction_summary()).unwrap()["is_settled"],
+        false
+    );
+
+    // 5.5 NEAR per token half way through, the last 6 tokens sell out the auction
+    context.block_timestamp(500);
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        6,
+        NearToken::from_near(33),
+    );
+
+    let summary = serde_json::to_value(contract.get_auction_summary()).unwrap();
+    assert_eq!(summary["is_settled"], true);
+    assert_eq!(
+        summary["total_proceeds"],
+        NearToken::from_near(55).as_yoctonear().to_string()
+    );
+
+    // The first buyer paid 10 NEAR per token and gets back the difference with the final price
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_yoctonear(0))
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.claim_tokens();
+    assert_eq!(contract.ft_balance_of(accounts(2)).0, 4);
+    assert_eq!(contract.get_auction_winner(None, None).len(), 2);
+}
+
+#[test]
+fn test_dutch_auction_settles_at_last_purchase_price() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            mode: Some(AuctionMode::Dutch(DutchAuction {
+                start_buy_amount: NearToken::from_near(100),
+                curve: PriceCurve::Linear,
+            })),
+            ..Default::default()
+        },
+    );
+
+    context.block_timestamp(500);
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        2,
+        NearToken::from_near(11),
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_yoctonear(0))
+        .block_timestamp(2_000)
+        .build());
+    contract.settle_auction();
+
+    let summary = serde_json::to_value(contract.get_auction_summary()).unwrap();
+    assert_eq!(
+        summary["total_proceeds"],
+        NearToken::from_near(11).as_yoctonear().to_string()
+    );
+    assert_eq!(contract.get_unsold_amount().0, 8);
+}
+
+#[test]
+#[should_panic(expected = "Offer price is less than the current price of the Dutch auction")]
+fn test_dutch_auction_purchase_below_current_price() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            mode: Some(AuctionMode::Dutch(DutchAuction {
+                start_buy_amount: NearToken::from_near(100),
+                curve: PriceCurve::Linear,
+            })),
+            ..Default::default()
+        },
+    );
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        4,
+        NearToken::from_near(20),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Purchases of a Dutch auction are final")]
+fn test_dutch_auction_purchase_can_not_be_cancelled() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            mode: Some(AuctionMode::Dutch(DutchAuction {
+                start_buy_amount: NearToken::from_near(100),
+                curve: PriceCurve::Linear,
+            })),
+            ..Default::default()
+        },
+    );
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        4,
+        NearToken::from_near(40),
+    );
+    contract.cancel_order(U64::from(0));
+}
+
+#[test]
+fn test_bonding_curve_buy_and_sell() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            mode: Some(AuctionMode::BondingCurve(BondingCurve {
+                start_buy_amount: NearToken::from_near(10),
+                shape: CurveShape::Linear {
+                    end_buy_amount: NearToken::from_near(30),
+                },
+            })),
+            ..Default::default()
+        },
+    );
+    register_bidder(&mut contract, &mut context, accounts(2));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(8))
+        .predecessor_account_id(accounts(2))
+        .build());
+    assert_eq!(
+        contract.get_buy_price(U128(5)),
+        NearToken::from_millinear(7_500)
+    );
+    let cost = contract.buy(U128(5), NearToken::from_near(8));
+    assert_eq!(cost, NearToken::from_millinear(7_500));
+    assert_eq!(contract.ft_balance_of(accounts(2)).0, 5);
+    assert!(get_logs()
+        .iter()
+        .any(|log| log.contains(r#""event":"tokens_bought""#)));
+
+    // Selling the tokens back returns what was paid for them
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_yoctonear(0))
+        .build());
+    contract.sell(U128(5), NearToken::from_near(7));
+    assert_eq!(contract.ft_balance_of(accounts(2)).0, 0);
+    assert!(get_logs()
+        .iter()
+        .any(|log| log.contains(r#""event":"tokens_sold""#)));
+
+    let summary = serde_json::to_value(contract.get_auction_summary()).unwrap();
+    assert_eq!(summary["curve_sold"], "0");
+    assert_eq!(summary["curve_reserve"], "0");
+}
+
+#[test]
+#[should_panic(expected = "is above max_in")]
+fn test_bonding_curve_buy_above_max_in() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            mode: Some(AuctionMode::BondingCurve(BondingCurve {
+                start_buy_amount: NearToken::from_near(10),
+                shape: CurveShape::Linear {
+                    end_buy_amount: NearToken::from_near(30),
+                },
+            })),
+            ..Default::default()
+        },
+    );
+    register_bidder(&mut contract, &mut context, accounts(2));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(8))
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.buy(U128(5), NearToken::from_near(7));
+}
+
+#[test]
+#[should_panic(expected = "are below min_out")]
+fn test_bonding_curve_sell_below_min_out() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            mode: Some(AuctionMode::BondingCurve(BondingCurve {
+                start_buy_amount: NearToken::from_near(10),
+                shape: CurveShape::Linear {
+                    end_buy_amount: NearToken::from_near(30),
+                },
+            })),
+            ..Default::default()
+        },
+    );
+    register_bidder(&mut contract, &mut context, accounts(2));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(8))
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.buy(U128(5), NearToken::from_near(8));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_yoctonear(0))
+        .build());
+    contract.sell(U128(5), NearToken::from_near(8));
+}
+
+#[test]
+#[should_panic(expected = "Buy amount is above the allocation cap of 2")]
+fn test_bonding_curve_buy_above_allocation_cap() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            mode: Some(AuctionMode::BondingCurve(BondingCurve {
+                start_buy_amount: NearToken::from_near(10),
+                shape: CurveShape::Linear {
+                    end_buy_amount: NearToken::from_near(30),
+                },
+            })),
+            allowlist_root: Some(allowlist_root()),
+            ..Default::default()
+        },
+    );
+    let (_, second) = allowlist_leaves();
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(
+            NearToken::from_near(10).saturating_add(contract.storage_balance_bounds().min)
+        )
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.join_allowlist(vec![Base64VecU8(second)], Some(U128(2)));
+    contract.buy(U128(2), NearToken::from_near(10));
+
+    // Selling the tokens back does not free the cap
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_yoctonear(0))
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.sell(U128(2), NearToken::from_yoctonear(0));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(10))
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.buy(U128(1), NearToken::from_near(10));
+}
+
+#[test]
+#[should_panic(expected = "Tokens of a bonding curve are traded with buy and sell")]
+fn test_place_order_on_bonding_curve() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            mode: Some(AuctionMode::BondingCurve(BondingCurve {
+                start_buy_amount: NearToken::from_near(10),
+                shape: CurveShape::Linear {
+                    end_buy_amount: NearToken::from_near(30),
+                },
+            })),
+            ..Default::default()
+        },
+    );
+    register_bidder(&mut contract, &mut context, accounts(2));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(20))
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.place_order(U128(5));
+}
+
+#[test]
+fn test_claim_vested() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    // 100 tokens vest between 1_000 and 2_000 with a cliff until 1_100
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            creator_allocation: Some(CreatorAllocation {
+                amount: U128(100),
+                cliff: U64(100),
+                duration: U64(1_000),
+            }),
+            ..Default::default()
+        },
+    );
+
+    // The allocation is minted but held by the contract
+    assert_eq!(contract.ft_total_supply().0, 110);
+    assert_eq!(contract.ft_balance_of(accounts(1)).0, 0);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .block_timestamp(1_500)
+        .build());
+    assert_eq!(contract.claim_vested().0, 50);
+    assert_eq!(contract.ft_balance_of(accounts(1)).0, 50);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .block_timestamp(3_000)
+        .build());
+    assert_eq!(contract.claim_vested().0, 50);
+
+    let vesting = serde_json::to_value(contract.get_vesting(accounts(1))).unwrap();
+    assert_eq!(vesting["total"], "100");
+    assert_eq!(vesting["claimed"], "100");
+    assert_eq!(vesting["cliff_end"], "1100");
+}
+
+#[test]
+#[should_panic(expected = "No vested tokens to claim")]
+fn test_claim_vested_before_cliff() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            creator_allocation: Some(CreatorAllocation {
+                amount: U128(100),
+                cliff: U64(100),
+                duration: U64(1_000),
+            }),
+            ..Default::default()
+        },
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .block_timestamp(1_050)
+        .build());
+    contract.claim_vested();
+}
+
+// Allowlist of two leaves: accounts(2) with a cap of 2 tokens and accounts(3) without a cap
+fn allowlist_leaves() -> (Vec<u8>, Vec<u8>) {
+    (
+        allowlist_leaf(&accounts(2), Some(U128(2))),
+        allowlist_leaf(&accounts(3), None),
+    )
+}
+
+fn allowlist_root() -> Base64VecU8 {
+    let (first, second) = allowlist_leaves();
+    Base64VecU8(allowlist_node(&first, &second))
+}
+
+#[test]
+fn test_place_order_on_allowlist() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            allowlist_root: Some(allowlist_root()),
+            ..Default::default()
+        },
+    );
+    let (first, second) = allowlist_leaves();
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    contract.join_allowlist(vec![Base64VecU8(second)], Some(U128(2)));
+    testing_env!(context.predecessor_account_id(accounts(3)).build());
+    contract.join_allowlist(vec![Base64VecU8(first)], None);
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        2,
+        NearToken::from_near(2),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        8,
+        NearToken::from_near(8),
+    );
+    assert_eq!(contract.get_orders(None, None).len(), 2);
+
+    let entry = serde_json::to_value(contract.get_allowlist_entry(accounts(2))).unwrap();
+    assert_eq!(entry["cap"], "2");
+}
+
+#[test]
+#[should_panic(expected = "Buy amount is above the allocation cap of 2")]
+fn test_place_order_above_allocation_cap() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            allowlist_root: Some(allowlist_root()),
+            ..Default::default()
+        },
+    );
+    let (_, second) = allowlist_leaves();
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    contract.join_allowlist(vec![Base64VecU8(second)], Some(U128(2)));
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        1,
+        NearToken::from_near(1),
+    );
+    // The cap counts every order of the bidder
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        2,
+        NearToken::from_near(2),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Account is not on the allowlist, join it with a proof first")]
+fn test_place_order_without_allowlist_proof() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            allowlist_root: Some(allowlist_root()),
+            ..Default::default()
+        },
+    );
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(4),
+        1,
+        NearToken::from_near(1),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Invalid allowlist proof")]
+fn test_join_allowlist_with_wrong_cap() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            allowlist_root: Some(allowlist_root()),
+            ..Default::default()
+        },
+    );
+    let (_, second) = allowlist_leaves();
+
+    testing_env!(context.predecessor_account_id(accounts(2)).build());
+    contract.join_allowlist(vec![Base64VecU8(second)], Some(U128(5)));
+}
+
+#[test]
+#[should_panic(expected = "Account is not on the allowlist, join it with a proof first")]
+fn test_rotate_allowlist_root() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = Contract::new(
+        accounts(1),
+        10.into(),
+        new_default_meta(),
+        DATA_IMAGE_SVG_NEAR_ICON.to_string(),
+        "New cool token to be aucted".to_string(),
+        AuctionConfig {
+            schedule: AuctionSchedule {
+                start: Some(U64(100)),
+                end: Some(U64(1_000)),
+                duration: None,
+            },
+            min_buy_amount: NearToken::from_near(10),
+            options: None,
+        },
+    );
+    contract.set_allowlist_root(Some(allowlist_root()));
+    let (first, _) = allowlist_leaves();
+
+    testing_env!(context.predecessor_account_id(accounts(3)).build());
+    contract.join_allowlist(vec![Base64VecU8(first)], None);
+    assert!(contract.get_allowlist_entry(accounts(3)).is_some());
+
+    // Rotating the root drops the proofs given for the previous one
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.set_allowlist_root(Some(Base64VecU8(vec![0; 32])));
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(2))
+        .predecessor_account_id(accounts(3))
+        .block_timestamp(200)
+        .build());
+    contract.place_order(U128(1));
+}
+
+#[test]
+#[should_panic(expected = "The allowlist can not be changed once bidding opens")]
+fn test_set_allowlist_root_after_start() {
+    let context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(accounts(1), AuctionOptions::default());
+
+    contract.set_allowlist_root(Some(allowlist_root()));
+}
+
+#[test]
+fn test_settle_with_max_buy_per_account() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            max_buy_per_account: Some(U128(4)),
+            ..Default::default()
+        },
+    );
+
+    // Together the orders of accounts(2) ask for the limit
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        2,
+        NearToken::from_near(6),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        2,
+        NearToken::from_near(4),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        4,
+        NearToken::from_near(6),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(4),
+        4,
+        NearToken::from_near(5),
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .block_timestamp(2_000)
+        .build());
+    contract.settle_auction();
+
+    let winners = serde_json::to_value(contract.get_auction_winner(None, None)).unwrap();
+    let winners = winners.as_array().unwrap();
+    assert_eq!(winners.len(), 4);
+    assert_eq!(winners[0][0]["bidder"], accounts(2).to_string());
+    assert_eq!(winners[1][0]["bidder"], accounts(2).to_string());
+    assert_eq!(winners[2][0]["bidder"], accounts(3).to_string());
+    assert_eq!(winners[3][0]["bidder"], accounts(4).to_string());
+    assert_eq!(winners[3][0]["buy_amount"], "2");
+}
+
+#[test]
+#[should_panic(expected = "Buy amount is above the limit of 4 tokens per account")]
+fn test_place_order_above_max_buy_per_account() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            max_buy_per_account: Some(U128(4)),
+            ..Default::default()
+        },
+    );
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        5,
+        NearToken::from_near(10),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Buy amount is above the limit of 4 tokens per account")]
+fn test_place_orders_above_max_buy_per_account() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            max_buy_per_account: Some(U128(4)),
+            ..Default::default()
+        },
+    );
+
+    // Every order fits the limit, the second one takes the bidder above it
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        3,
+        NearToken::from_near(12),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        2,
+        NearToken::from_near(8),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Buy amount is above the limit of 4 tokens per account")]
+fn test_amend_order_above_max_buy_per_account() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            max_buy_per_account: Some(U128(4)),
+            ..Default::default()
+        },
+    );
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        2,
+        NearToken::from_near(12),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        2,
+        NearToken::from_near(8),
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.amend_order(U64(1), U128(3), NearToken::from_near(12));
+}
+
+#[test]
+#[should_panic(expected = "Buy amount is above the limit of 4 tokens per account")]
+fn test_bonding_curve_buy_above_max_buy_per_account() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            mode: Some(AuctionMode::BondingCurve(BondingCurve {
+                start_buy_amount: NearToken::from_near(10),
+                shape: CurveShape::Linear {
+                    end_buy_amount: NearToken::from_near(30),
+                },
+            })),
+            max_buy_per_account: Some(U128(4)),
+            ..Default::default()
+        },
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(
+            NearToken::from_near(20).saturating_add(contract.storage_balance_bounds().min)
+        )
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.buy(U128(3), NearToken::from_near(20));
+    contract.buy(U128(2), NearToken::from_near(20));
+}
+
+fn auction_end(contract: &Contract) -> serde_json::Value {
+    serde_json::to_value(contract.get_auction_countdown()).unwrap()["auction_end"].clone()
+}
+
+fn place_order_at(contract: &mut Contract, context: &mut VMContextBuilder, timestamp: u64) {
+    context.block_timestamp(timestamp);
+    register_and_place_order(contract, context, accounts(2), 1, NearToken::from_near(2));
+}
+
+#[test]
+fn test_soft_close_extends_auction_end() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            soft_close: Some(SoftClose {
+                window: U64(100),
+                extension: U64(200),
+                max_extension: U64(300),
+            }),
+            ..Default::default()
+        },
+    );
+
+    // Before the last window the end does not move
+    place_order_at(&mut contract, &mut context, 500);
+    assert_eq!(auction_end(&contract), "1000");
+
+    place_order_at(&mut contract, &mut context, 950);
+    assert_eq!(auction_end(&contract), "1200");
+
+    // The end is pushed out at most max_extension past the scheduled end
+    place_order_at(&mut contract, &mut context, 1_150);
+    assert_eq!(auction_end(&contract), "1300");
+    place_order_at(&mut contract, &mut context, 1_250);
+    assert_eq!(auction_end(&contract), "1300");
+
+    let summary = serde_json::to_value(contract.get_auction_summary()).unwrap();
+    assert_eq!(summary["scheduled_end"], "1000");
+    assert_eq!(summary["reveal_end"], "1300");
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .block_timestamp(1_400)
+        .build());
+    contract.settle_auction();
+    assert_eq!(
+        serde_json::to_value(contract.get_auction_summary()).unwrap()["is_settled"],
+        true
+    );
+}
+
+#[test]
+#[should_panic(expected = "Auction has not ended yet, cannot calculate winning orders")]
+fn test_settle_before_extended_end() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            soft_close: Some(SoftClose {
+                window: U64(100),
+                extension: U64(200),
+                max_extension: U64(300),
+            }),
+            ..Default::default()
+        },
+    );
+    place_order_at(&mut contract, &mut context, 950);
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .block_timestamp(1_100)
+        .build());
+    contract.settle_auction();
+}
+
+fn find_event(event: &str) -> serde_json::Value {
+    let log = get_logs()
+        .into_iter()
+        .find(|log| log.contains(&format!(r#""event":"{}""#, event)))
+        .unwrap_or_else(|| panic!("No {} event", event));
+    let event: serde_json::Value = serde_json::from_str(&log["EVENT_JSON:".len()..]).unwrap();
+    assert_eq!(event["standard"], "near_pump_auction");
+    assert_eq!(event["version"], "1.1.0");
+    event["data"][0].clone()
+}
+
+#[test]
+fn test_auction_lifecycle_events() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(accounts(1), AuctionOptions::default());
+
+    // The bidder is registered by its first order
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(11))
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.place_order(U128(10));
+    assert_eq!(
+        find_event("bidder_registered")["account_id"],
+        accounts(2).to_string()
+    );
+    let order_placed = find_event("order_placed");
+    assert_eq!(order_placed["order_id"], "0");
+    assert_eq!(order_placed["buy_amount"], "10");
+
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        10,
+        NearToken::from_near(10),
+    );
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .block_timestamp(2_000)
+        .build());
+    contract.settle_auction();
+    let auction_settled = find_event("auction_settled");
+    assert_eq!(auction_settled["is_failed"], false);
+    assert_eq!(auction_settled["filled_amount"], "10");
+    assert_eq!(auction_settled["winning_orders"], "1");
+    assert_eq!(auction_settled["clearing_price"]["buy_amount"], "10");
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .predecessor_account_id(accounts(2))
+        .build());
+    contract.claim_tokens();
+    let tokens_claimed = find_event("tokens_claimed");
+    assert_eq!(tokens_claimed["token_amount"], "10");
+    assert_eq!(tokens_claimed["order_ids"][0], "0");
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .predecessor_account_id(accounts(3))
+        .build());
+    contract.refund_deposit();
+    let deposit_refunded = find_event("deposit_refunded");
+    assert_eq!(deposit_refunded["account_id"], accounts(3).to_string());
+    assert_eq!(
+        deposit_refunded["amount"],
+        NearToken::from_near(10).as_yoctonear().to_string()
+    );
+}
+
+fn auction_status(contract: &Contract) -> serde_json::Value {
+    serde_json::to_value(contract.get_auction_status()).unwrap()
+}
+
+#[test]
+fn test_auction_status() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = Contract::new(
+        accounts(1),
+        10.into(),
+        new_default_meta(),
+        DATA_IMAGE_SVG_NEAR_ICON.to_string(),
+        "New cool token to be aucted".to_string(),
+        AuctionConfig {
+            schedule: AuctionSchedule {
+                start: Some(U64(100)),
+                end: Some(U64(1_000)),
+                duration: None,
+            },
+            min_buy_amount: NearToken::from_near(10),
+            options: None,
+        },
+    );
+    assert_eq!(auction_status(&contract)["status"], "Scheduled");
+
+    context.block_timestamp(500);
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        4,
+        NearToken::from_near(5),
+    );
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(3),
+        6,
+        NearToken::from_near(7),
+    );
+    let status = auction_status(&contract);
+    assert_eq!(status["status"], "Open");
+    assert_eq!(status["since"], "100");
+
+    // The status follows the clock even before a method stores it
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .attached_deposit(NearToken::from_near(0))
+        .block_timestamp(1_000)
+        .build());
+    let status = auction_status(&contract);
+    assert_eq!(status["status"], "Ended");
+    assert_eq!(status["since"], "1000");
+    let info = serde_json::to_value(contract.get_auction_info()).unwrap();
+    assert_eq!(info["status"], "Ended");
+
+    // Without sealed bids the settlement can start right at the end
+    assert!(!contract.settle_auction_step(U64(1)));
+    let status = auction_status(&contract);
+    assert_eq!(status["status"], "Settling");
+    assert_eq!(status["since"], "1000");
+
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .block_timestamp(3_000)
+        .build());
+    assert!(contract.settle_auction_step(U64(1)));
+    let status = auction_status(&contract);
+    assert_eq!(status["status"], "Settled");
+    assert_eq!(status["since"], "3000");
+}
+
+#[test]
+fn test_auction_status_failed() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(
+        accounts(1),
+        AuctionOptions {
+            min_raise: Some(NearToken::from_near(100)),
+            ..Default::default()
+        },
+    );
+    place_orders_and_settle(&mut contract, &mut context);
+
+    assert_eq!(auction_status(&contract)["status"], "Failed");
+}
+
+#[test]
+#[should_panic(expected = "Auction is cancelled")]
+fn test_cancel_auction() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = Contract::new(
+        accounts(1),
+        10.into(),
+        new_default_meta(),
+        DATA_IMAGE_SVG_NEAR_ICON.to_string(),
+        "New cool token to be aucted".to_string(),
+        AuctionConfig {
+            schedule: AuctionSchedule {
+                start: Some(U64(100)),
+                end: Some(U64(1_000)),
+                duration: None,
+            },
+            min_buy_amount: NearToken::from_near(10),
+            options: None,
+        },
+    );
+
+    contract.cancel_auction();
+    assert_eq!(auction_status(&contract)["status"], "Cancelled");
+    // The supply goes back to the auctioner
+    assert_eq!(contract.ft_balance_of(accounts(1)).0, 10);
+
+    context.block_timestamp(500);
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        4,
+        NearToken::from_near(5),
+    );
+}
+
+#[test]
+#[should_panic(expected = "An auction can not be cancelled once it has bids")]
+fn test_cancel_auction_with_bids() {
+    let mut context = get_context(accounts(1));
+    testing_env!(context.build());
+    let mut contract = init_auction_with_options(accounts(1), AuctionOptions::default());
+    register_and_place_order(
+        &mut contract,
+        &mut context,
+        accounts(2),
+        4,
+        NearToken::from_near(5),
+    );
+
+    testing_env!(context.predecessor_account_id(accounts(1)).build());
+    contract.cancel_auction();
+}
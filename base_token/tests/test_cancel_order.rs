@@ -0,0 +1,90 @@
+use chrono::Utc;
+use near_workspaces::types::NearToken;
+
+mod common;
+
+use common::builder::ContractBuilder;
+use common::utils::{
+    cancel_order, create_subaccount, init_contract, place_order, register_token_account, Order,
+};
+
+#[tokio::test]
+async fn test_cancel_order_refunds_deposit() -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = near_workspaces::sandbox().await?;
+    let root = sandbox.root_account()?;
+
+    let alice = create_subaccount(&root, "alice").await?;
+    let bob = create_subaccount(&root, "bob").await?;
+    let contract_account = create_subaccount(&root, "contract").await?;
+
+    let contract_wasm = near_workspaces::compile_project("./").await?;
+    let contract = contract_account.deploy(&contract_wasm).await?.unwrap();
+
+    let now = Utc::now().timestamp();
+    let init_args = ContractBuilder::new(root.id().to_string())
+        .total_supply(100)
+        .auction_end(((now + 600) as u64) * 1000000000)
+        .min_buy_amount(NearToken::from_near(50).as_yoctonear())
+        .build();
+    init_contract(&contract, init_args).await?;
+
+    let _ = register_token_account(&alice, &contract).await?;
+    let _ = register_token_account(&bob, &contract).await?;
+
+    let _ = place_order(&alice, &contract, 10, NearToken::from_near(10)).await?;
+    let _ = place_order(&bob, &contract, 10, NearToken::from_near(20)).await?;
+
+    // Bob can not cancel alice's order
+    let bob_cancel = cancel_order(&bob, &contract, 0).await?;
+    assert!(bob_cancel.is_failure());
+
+    let balance_before_cancel = alice.view_account().await?.balance;
+    let alice_cancel = cancel_order(&alice, &contract, 0).await?;
+    assert!(alice_cancel.is_success());
+    let balance_after_cancel = alice.view_account().await?.balance;
+    assert!(
+        balance_after_cancel.as_yoctonear()
+            > balance_before_cancel.as_yoctonear() + NearToken::from_near(9).as_yoctonear()
+    );
+
+    let orders = contract
+        .call("get_orders")
+        .args_json(serde_json::json!({}))
+        .transact()
+        .await?;
+    let orders_json = orders.json::<Vec<Order>>()?;
+    assert_eq!(orders_json.len(), 1);
+    assert_eq!(orders_json[0].bidder, "bob.test.near");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cancel_order_after_auction_end() -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = near_workspaces::sandbox().await?;
+    let root = sandbox.root_account()?;
+
+    let alice = create_subaccount(&root, "alice").await?;
+    let contract_account = create_subaccount(&root, "contract").await?;
+
+    let contract_wasm = near_workspaces::compile_project("./").await?;
+    let contract = contract_account.deploy(&contract_wasm).await?.unwrap();
+
+    let now = Utc::now().timestamp();
+    let init_args = ContractBuilder::new(root.id().to_string())
+        .total_supply(100)
+        .auction_end(((now + 60) as u64) * 1000000000)
+        .min_buy_amount(NearToken::from_near(50).as_yoctonear())
+        .build();
+    init_contract(&contract, init_args).await?;
+
+    let _ = register_token_account(&alice, &contract).await?;
+    let _ = place_order(&alice, &contract, 10, NearToken::from_near(10)).await?;
+
+    sandbox.fast_forward(2000).await?;
+
+    let alice_cancel = cancel_order(&alice, &contract, 0).await?;
+    assert!(alice_cancel.is_failure());
+
+    Ok(())
+}
@@ -5,8 +5,9 @@ mod common;
 
 use common::builder::ContractBuilder;
 use common::utils::{
-    check_balance, claim_tokens, create_subaccount, init_contract, place_order, refund_deposit,
-    register_token_account, settle_auction,
+    check_balance, claim_all, claim_tokens, create_subaccount, distribute, init_contract,
+    place_order, refund_all, refund_deposit, register_token_account, settle_auction,
+    withdraw_proceeds,
 };
 
 #[tokio::test]
@@ -25,7 +26,7 @@ async fn test_claim_and_refund() -> Result<(), Box<dyn std::error::Error>> {
     let now = Utc::now().timestamp();
     let init_args = ContractBuilder::new(root.id().to_string())
         .total_supply(100)
-        .auction_duration(((now + 60) as u64) * 1000000000)
+        .auction_end(((now + 60) as u64) * 1000000000)
         .min_buy_amount(NearToken::from_near(50).as_yoctonear())
         .build();
     init_contract(&contract, init_args).await?;
@@ -63,3 +64,204 @@ async fn test_claim_and_refund() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_claim_all_and_refund_all_with_multiple_orders(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = near_workspaces::sandbox().await?;
+    let root = sandbox.root_account()?;
+
+    let alice = create_subaccount(&root, "alice").await?;
+    let bob = create_subaccount(&root, "bob").await?;
+    let contract_account = create_subaccount(&root, "contract").await?;
+
+    let contract_wasm = near_workspaces::compile_project("./").await?;
+    let contract = contract_account.deploy(&contract_wasm).await?.unwrap();
+
+    let now = Utc::now().timestamp();
+    let init_args = ContractBuilder::new(root.id().to_string())
+        .total_supply(100)
+        .auction_end(((now + 60) as u64) * 1000000000)
+        .min_buy_amount(NearToken::from_near(50).as_yoctonear())
+        .build();
+    init_contract(&contract, init_args).await?;
+
+    let _ = register_token_account(&alice, &contract).await?;
+    let _ = register_token_account(&bob, &contract).await?;
+
+    // Alice wins with her first two orders and loses with the last one
+    let _ = place_order(&alice, &contract, 40, NearToken::from_near(40)).await?;
+    let _ = place_order(&alice, &contract, 30, NearToken::from_near(30)).await?;
+    let _ = place_order(&bob, &contract, 30, NearToken::from_near(30)).await?;
+    let _ = place_order(&alice, &contract, 10, NearToken::from_near(5)).await?;
+
+    sandbox.fast_forward(2000).await?;
+
+    let _ = settle_auction(&contract).await?;
+
+    let alice_claim = claim_all(&alice, &contract).await?;
+    assert!(alice_claim.is_success());
+
+    let alice_balance = check_balance(&alice, &contract).await?;
+    assert_eq!(alice_balance.0, 70);
+
+    // Every winning order of alice was claimed in the previous call
+    let alice_second_claim = claim_tokens(&alice, &contract).await?;
+    assert!(alice_second_claim.is_failure());
+
+    let alice_refund = refund_all(&alice, &contract).await?;
+    assert!(alice_refund.is_success());
+
+    let alice_second_refund = refund_deposit(&alice, &contract).await?;
+    assert!(alice_second_refund.is_failure());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_refund_when_auction_fails_below_min_raise() -> Result<(), Box<dyn std::error::Error>>
+{
+    let sandbox = near_workspaces::sandbox().await?;
+    let root = sandbox.root_account()?;
+
+    let alice = create_subaccount(&root, "alice").await?;
+    let bob = create_subaccount(&root, "bob").await?;
+    let contract_account = create_subaccount(&root, "contract").await?;
+
+    let contract_wasm = near_workspaces::compile_project("./").await?;
+    let contract = contract_account.deploy(&contract_wasm).await?.unwrap();
+
+    let now = Utc::now().timestamp();
+    let init_args = ContractBuilder::new(root.id().to_string())
+        .total_supply(100)
+        .auction_end(((now + 60) as u64) * 1000000000)
+        .min_buy_amount(NearToken::from_near(50).as_yoctonear())
+        .min_raise(NearToken::from_near(200).as_yoctonear())
+        .build();
+    init_contract(&contract, init_args).await?;
+
+    let _ = register_token_account(&alice, &contract).await?;
+    let _ = register_token_account(&bob, &contract).await?;
+
+    let _ = place_order(&alice, &contract, 60, NearToken::from_near(70)).await?;
+    let _ = place_order(&bob, &contract, 50, NearToken::from_near(55)).await?;
+
+    sandbox.fast_forward(2000).await?;
+
+    let _ = settle_auction(&contract).await?;
+
+    // Nobody wins, every bidder gets the whole deposit back
+    let alice_claim = claim_tokens(&alice, &contract).await?;
+    assert!(alice_claim.is_failure());
+
+    let alice_refund = refund_deposit(&alice, &contract).await?;
+    assert!(alice_refund.is_success());
+
+    let bob_refund = refund_deposit(&bob, &contract).await?;
+    assert!(bob_refund.is_success());
+
+    let auctioner_balance = check_balance(&root, &contract).await?;
+    assert_eq!(auctioner_balance.0, 100);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_withdraw_proceeds() -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = near_workspaces::sandbox().await?;
+    let root = sandbox.root_account()?;
+
+    let alice = create_subaccount(&root, "alice").await?;
+    let bob = create_subaccount(&root, "bob").await?;
+    let contract_account = create_subaccount(&root, "contract").await?;
+
+    let contract_wasm = near_workspaces::compile_project("./").await?;
+    let contract = contract_account.deploy(&contract_wasm).await?.unwrap();
+
+    let now = Utc::now().timestamp();
+    let init_args = ContractBuilder::new(root.id().to_string())
+        .total_supply(100)
+        .auction_end(((now + 60) as u64) * 1000000000)
+        .min_buy_amount(NearToken::from_near(50).as_yoctonear())
+        .build();
+    init_contract(&contract, init_args).await?;
+
+    let _ = register_token_account(&alice, &contract).await?;
+    let _ = register_token_account(&bob, &contract).await?;
+
+    let _ = place_order(&alice, &contract, 60, NearToken::from_near(70)).await?;
+    let _ = place_order(&bob, &contract, 50, NearToken::from_near(55)).await?;
+
+    sandbox.fast_forward(2000).await?;
+
+    // Only the auctioner can withdraw and only after the settlement
+    let early_withdraw = withdraw_proceeds(&root, &contract).await?;
+    assert!(early_withdraw.is_failure());
+
+    let _ = settle_auction(&contract).await?;
+
+    let bidder_withdraw = withdraw_proceeds(&alice, &contract).await?;
+    assert!(bidder_withdraw.is_failure());
+
+    let balance_before = root.view_account().await?.balance;
+    let withdraw = withdraw_proceeds(&root, &contract).await?;
+    assert!(withdraw.is_success());
+    let balance_after = root.view_account().await?.balance;
+
+    // 100 tokens at 1.1 NEAR, minus the gas of the call
+    assert!(balance_after > balance_before.saturating_add(NearToken::from_near(109)));
+
+    let second_withdraw = withdraw_proceeds(&root, &contract).await?;
+    assert!(second_withdraw.is_failure());
+
+    // Bob's refund is still covered
+    let bob_claim = claim_tokens(&bob, &contract).await?;
+    assert!(bob_claim.is_success());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_distribute() -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = near_workspaces::sandbox().await?;
+    let root = sandbox.root_account()?;
+
+    let alice = create_subaccount(&root, "alice").await?;
+    let bob = create_subaccount(&root, "bob").await?;
+    let contract_account = create_subaccount(&root, "contract").await?;
+
+    let contract_wasm = near_workspaces::compile_project("./").await?;
+    let contract = contract_account.deploy(&contract_wasm).await?.unwrap();
+
+    let now = Utc::now().timestamp();
+    let init_args = ContractBuilder::new(root.id().to_string())
+        .total_supply(100)
+        .auction_end(((now + 60) as u64) * 1000000000)
+        .min_buy_amount(NearToken::from_near(50).as_yoctonear())
+        .build();
+    init_contract(&contract, init_args).await?;
+
+    let _ = register_token_account(&alice, &contract).await?;
+    let _ = register_token_account(&bob, &contract).await?;
+
+    let _ = place_order(&alice, &contract, 60, NearToken::from_near(70)).await?;
+    let _ = place_order(&bob, &contract, 50, NearToken::from_near(55)).await?;
+
+    sandbox.fast_forward(2000).await?;
+    let _ = settle_auction(&contract).await?;
+
+    // Anyone can push the tokens out, one order per call here
+    assert!(!distribute(&root, &contract, 1).await?);
+    assert!(distribute(&root, &contract, 10).await?);
+
+    let alice_balance = check_balance(&alice, &contract).await?;
+    let bob_balance = check_balance(&bob, &contract).await?;
+    assert_eq!(alice_balance.0, 60);
+    assert_eq!(bob_balance.0, 40);
+
+    // The orders are already paid out
+    let alice_claim = claim_tokens(&alice, &contract).await?;
+    assert!(alice_claim.is_failure());
+
+    Ok(())
+}
@@ -27,7 +27,7 @@ async fn test_calculate_gas_with_one_bidder() -> Result<(), Box<dyn std::error::
     let now = Utc::now().timestamp();
     let init_args = ContractBuilder::new(root.id().to_string())
         .total_supply(TOTAL_SUPPLY)
-        .auction_duration(((now + 60) as u64) * 1000000000)
+        .auction_end(((now + 60) as u64) * 1000000000)
         .min_buy_amount(NearToken::from_near(50).as_yoctonear())
         .build();
 
@@ -87,7 +87,7 @@ async fn test_calculate_gas_with_ten_bidders() -> Result<(), Box<dyn std::error:
     let now = Utc::now().timestamp();
     let init_args = ContractBuilder::new(root.id().to_string())
         .total_supply(TOTAL_SUPPLY)
-        .auction_duration(((now + 60) as u64) * 1000000000)
+        .auction_end(((now + 60) as u64) * 1000000000)
         .min_buy_amount(NearToken::from_near(50).as_yoctonear())
         .build();
 
@@ -180,7 +180,7 @@ async fn test_calculate_gas_with_hundred_bidders() -> Result<(), Box<dyn std::er
     let now = Utc::now().timestamp();
     let init_args = ContractBuilder::new(root.id().to_string())
         .total_supply(TOTAL_SUPPLY)
-        .auction_duration(((now + 600) as u64) * 1000000000)
+        .auction_end(((now + 600) as u64) * 1000000000)
         .min_buy_amount(NearToken::from_near(50).as_yoctonear())
         .build();
 
@@ -19,7 +19,7 @@ async fn test_place_one_order() -> Result<(), Box<dyn std::error::Error>> {
 
     let now = Utc::now().timestamp();
     let init_args = ContractBuilder::new(root.id().to_string())
-        .auction_duration(((now + 60) as u64) * 1000000000)
+        .auction_end(((now + 60) as u64) * 1000000000)
         .build();
     init_contract(&contract, init_args).await?;
 
@@ -28,7 +28,11 @@ async fn test_place_one_order() -> Result<(), Box<dyn std::error::Error>> {
     let alice_order = place_order(&alice, &contract, 1, NearToken::from_near(1)).await?;
     assert!(alice_order.is_success());
 
-    let orders = contract.call("get_orders").transact().await?;
+    let orders = contract
+        .call("get_orders")
+        .args_json(serde_json::json!({}))
+        .transact()
+        .await?;
     let orders_json = orders.json::<Vec<Order>>()?;
 
     assert_eq!(orders_json.len(), 1);
@@ -54,7 +58,7 @@ async fn test_place_multiple_orders() -> Result<(), Box<dyn std::error::Error>>
     let now = Utc::now().timestamp();
     let init_args = ContractBuilder::new(root.id().to_string())
         .total_supply(100)
-        .auction_duration(((now + 60) as u64) * 1000000000)
+        .auction_end(((now + 60) as u64) * 1000000000)
         .min_buy_amount(NearToken::from_near(50).as_yoctonear())
         .build();
     init_contract(&contract, init_args).await?;
@@ -68,7 +72,11 @@ async fn test_place_multiple_orders() -> Result<(), Box<dyn std::error::Error>>
     assert!(alice_order.is_success());
     assert!(bob_order.is_success());
 
-    let orders = contract.call("get_orders").transact().await?;
+    let orders = contract
+        .call("get_orders")
+        .args_json(serde_json::json!({}))
+        .transact()
+        .await?;
     let orders_json = orders.json::<Vec<Order>>()?;
 
     assert_eq!(orders_json.len(), 2);
@@ -82,3 +90,93 @@ async fn test_place_multiple_orders() -> Result<(), Box<dyn std::error::Error>>
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_get_orders_with_pagination_and_bidder_filter(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = near_workspaces::sandbox().await?;
+    let root = sandbox.root_account()?;
+
+    let alice = create_subaccount(&root, "alice").await?;
+    let bob = create_subaccount(&root, "bob").await?;
+    let contract_account = create_subaccount(&root, "contract").await?;
+
+    let contract_wasm = near_workspaces::compile_project("./").await?;
+    let contract = contract_account.deploy(&contract_wasm).await?.unwrap();
+
+    let now = Utc::now().timestamp();
+    let init_args = ContractBuilder::new(root.id().to_string())
+        .total_supply(100)
+        .auction_end(((now + 60) as u64) * 1000000000)
+        .min_buy_amount(NearToken::from_near(50).as_yoctonear())
+        .build();
+    init_contract(&contract, init_args).await?;
+
+    let _ = register_token_account(&alice, &contract).await?;
+    let _ = register_token_account(&bob, &contract).await?;
+
+    let _ = place_order(&alice, &contract, 1, NearToken::from_near(1)).await?;
+    let _ = place_order(&bob, &contract, 2, NearToken::from_near(5)).await?;
+    let _ = place_order(&alice, &contract, 3, NearToken::from_near(6)).await?;
+
+    let page = contract
+        .call("get_orders")
+        .args_json(serde_json::json!({ "from_index": "1", "limit": "2" }))
+        .transact()
+        .await?;
+    let page_json = page.json::<Vec<Order>>()?;
+
+    assert_eq!(page_json.len(), 2);
+    assert_eq!(page_json[0].bidder, "bob.test.near");
+    assert_eq!(page_json[1].bidder, "alice.test.near");
+
+    let alice_orders = contract
+        .call("get_orders_by_bidder")
+        .args_json(serde_json::json!({ "account_id": alice.id() }))
+        .transact()
+        .await?;
+    let alice_orders_json = alice_orders.json::<Vec<Order>>()?;
+
+    assert_eq!(alice_orders_json.len(), 2);
+    assert_eq!(alice_orders_json[0].id, "0");
+    assert_eq!(alice_orders_json[1].id, "2");
+
+    let summary = contract.call("get_auction_summary").transact().await?;
+    let summary_json = summary.json::<serde_json::Value>()?;
+    assert_eq!(summary_json["total_orders"], "3");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_place_order_before_auction_start() -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = near_workspaces::sandbox().await?;
+    let root = sandbox.root_account()?;
+
+    let alice = create_subaccount(&root, "alice").await?;
+    let contract_account = create_subaccount(&root, "contract").await?;
+
+    let contract_wasm = near_workspaces::compile_project("./").await?;
+    let contract = contract_account.deploy(&contract_wasm).await?.unwrap();
+
+    let now = Utc::now().timestamp();
+    let init_args = ContractBuilder::new(root.id().to_string())
+        .auction_start(((now + 3600) as u64) * 1000000000)
+        .auction_end(((now + 7200) as u64) * 1000000000)
+        .build();
+    init_contract(&contract, init_args).await?;
+
+    let _ = register_token_account(&alice, &contract).await?;
+
+    let alice_order = place_order(&alice, &contract, 1, NearToken::from_near(1)).await?;
+    assert!(alice_order.is_failure());
+
+    let countdown = contract
+        .view("get_auction_countdown")
+        .args_json(serde_json::json!({}))
+        .await?
+        .json::<serde_json::Value>()?;
+    assert_ne!(countdown["starts_in"], "0");
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use chrono::Utc;
+use near_sdk::json_types::U128;
+use near_workspaces::types::NearToken;
+
+mod common;
+
+use common::builder::ContractBuilder;
+use common::utils::{
+    check_balance, claim_tokens, create_subaccount, init_contract, register_token_account,
+    settle_auction,
+};
+
+async fn quote_balance(
+    account: &near_workspaces::Account,
+    quote_token: &near_workspaces::Contract,
+) -> Result<u128, Box<dyn std::error::Error>> {
+    let balance: U128 = quote_token
+        .call("ft_balance_of")
+        .args_json(serde_json::json!({ "account_id": account.id() }))
+        .view()
+        .await?
+        .json()?;
+    Ok(balance.0)
+}
+
+async fn bid_in_quote_token(
+    account: &near_workspaces::Account,
+    quote_token: &near_workspaces::Contract,
+    contract: &near_workspaces::Contract,
+    buy_amount: u128,
+    sell_amount: u128,
+) -> Result<near_workspaces::result::ExecutionFinalResult, Box<dyn std::error::Error>> {
+    let result = account
+        .call(quote_token.id(), "ft_transfer_call")
+        .args_json(serde_json::json!({
+            "receiver_id": contract.id(),
+            "amount": U128(sell_amount),
+            "msg": serde_json::json!({ "buy_amount": U128(buy_amount) }).to_string()
+        }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    Ok(result)
+}
+
+#[tokio::test]
+async fn test_bid_and_settle_in_quote_token() -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = near_workspaces::sandbox().await?;
+    let root = sandbox.root_account()?;
+
+    let alice = create_subaccount(&root, "alice").await?;
+    let bob = create_subaccount(&root, "bob").await?;
+    let usd_account = create_subaccount(&root, "usd").await?;
+    let contract_account = create_subaccount(&root, "contract").await?;
+
+    let usd_wasm = near_workspaces::compile_project("./tests/mock_ft").await?;
+    let usd = usd_account.deploy(&usd_wasm).await?.unwrap();
+    let _ = usd
+        .call("new")
+        .args_json(serde_json::json!({
+            "owner_id": root.id(),
+            "total_supply": U128(1_000_000)
+        }))
+        .transact()
+        .await?;
+
+    for account in [&alice, &bob, &contract_account] {
+        let _ = account
+            .call(usd.id(), "storage_deposit")
+            .deposit(NearToken::from_millinear(125))
+            .transact()
+            .await?;
+    }
+    for account in [&alice, &bob] {
+        let _ = root
+            .call(usd.id(), "ft_transfer")
+            .args_json(serde_json::json!({ "receiver_id": account.id(), "amount": U128(1_000) }))
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+            .await?;
+    }
+
+    let contract_wasm = near_workspaces::compile_project("./").await?;
+    let contract = contract_account.deploy(&contract_wasm).await?.unwrap();
+
+    let now = Utc::now().timestamp();
+    let init_args = ContractBuilder::new(root.id().to_string())
+        .total_supply(100)
+        .auction_end(((now + 60) as u64) * 1000000000)
+        .min_buy_amount(100)
+        .quote_token(usd.id().to_string())
+        .build();
+    init_contract(&contract, init_args).await?;
+
+    let _ = register_token_account(&alice, &contract).await?;
+    let _ = register_token_account(&bob, &contract).await?;
+
+    // 2 USD per token for alice, 1 USD per token for bob, who only gets the 40 tokens left
+    let alice_bid = bid_in_quote_token(&alice, &usd, &contract, 60, 120).await?;
+    assert!(alice_bid.is_success());
+    let bob_bid = bid_in_quote_token(&bob, &usd, &contract, 50, 50).await?;
+    assert!(bob_bid.is_success());
+    assert_eq!(quote_balance(&alice, &usd).await?, 880);
+
+    // A bid below the minimum price is refunded by the quote token
+    let low_bid = bid_in_quote_token(&bob, &usd, &contract, 100, 10).await?;
+    assert!(low_bid.is_success());
+    assert_eq!(quote_balance(&bob, &usd).await?, 950);
+
+    sandbox.fast_forward(2000).await?;
+    let _ = settle_auction(&contract).await?;
+
+    let alice_claim = claim_tokens(&alice, &contract).await?;
+    assert!(alice_claim.is_success());
+    let bob_claim = claim_tokens(&bob, &contract).await?;
+    assert!(bob_claim.is_success());
+
+    // Cleared at 1 USD per token, alice gets 60 USD back and bob 10 USD
+    assert_eq!(check_balance(&alice, &contract).await?.0, 60);
+    assert_eq!(check_balance(&bob, &contract).await?.0, 40);
+    assert_eq!(quote_balance(&alice, &usd).await?, 940);
+    assert_eq!(quote_balance(&bob, &usd).await?, 960);
+
+    Ok(())
+}
@@ -7,7 +7,7 @@ use near_workspaces::types::NearToken;
 use common::builder::ContractBuilder;
 use common::utils::{
     create_subaccount, create_subaccount_with_balance, init_contract, place_order,
-    register_token_account, settle_auction, winner_list, Order,
+    register_token_account, settle_auction, settle_auction_step, winner_list, Order,
 };
 
 #[tokio::test]
@@ -25,7 +25,7 @@ async fn test_simple_settle_auction() -> Result<(), Box<dyn std::error::Error>>
     let now = Utc::now().timestamp();
     let init_args = ContractBuilder::new(root.id().to_string())
         .total_supply(100)
-        .auction_duration(((now + 600) as u64) * 1000000000)
+        .auction_end(((now + 600) as u64) * 1000000000)
         .min_buy_amount(NearToken::from_near(50).as_yoctonear())
         .build();
     init_contract(&contract, init_args).await?;
@@ -36,7 +36,11 @@ async fn test_simple_settle_auction() -> Result<(), Box<dyn std::error::Error>>
     let _ = place_order(&alice, &contract, 1, NearToken::from_near(10)).await?;
     let _ = place_order(&bob, &contract, 2, NearToken::from_near(10)).await?;
 
-    let unsorted_orders = contract.call("get_orders").transact().await?;
+    let unsorted_orders = contract
+        .call("get_orders")
+        .args_json(serde_json::json!({}))
+        .transact()
+        .await?;
     let unsorted_orders_json = unsorted_orders.json::<Vec<Order>>()?;
     assert_eq!(unsorted_orders_json.len(), 2);
     assert_eq!(unsorted_orders_json[0].bidder, "alice.test.near");
@@ -47,7 +51,11 @@ async fn test_simple_settle_auction() -> Result<(), Box<dyn std::error::Error>>
     let settle_auction = settle_auction(&contract).await?;
     assert!(settle_auction.is_success());
 
-    let sorted_orders = contract.call("get_orders").transact().await?;
+    let sorted_orders = contract
+        .call("get_orders")
+        .args_json(serde_json::json!({}))
+        .transact()
+        .await?;
     let sorted_orders_json = sorted_orders.json::<Vec<Order>>()?;
 
     assert_eq!(sorted_orders_json.len(), 2);
@@ -87,7 +95,7 @@ async fn test_settle_auction_when_user_buy_almost_all_tokens_but_offer_price_is_
     let now = Utc::now().timestamp();
     let init_args = ContractBuilder::new(root.id().to_string())
         .total_supply(100)
-        .auction_duration(((now + 60) as u64) * 1000000000)
+        .auction_end(((now + 60) as u64) * 1000000000)
         .min_buy_amount(NearToken::from_near(100).as_yoctonear())
         .build();
     init_contract(&contract, init_args).await?;
@@ -98,7 +106,11 @@ async fn test_settle_auction_when_user_buy_almost_all_tokens_but_offer_price_is_
     let _ = place_order(&alice, &contract, 90, NearToken::from_near(90)).await?;
     let _ = place_order(&bob, &contract, 20, NearToken::from_near(20)).await?;
 
-    let unsorted_orders = contract.call("get_orders").transact().await?;
+    let unsorted_orders = contract
+        .call("get_orders")
+        .args_json(serde_json::json!({}))
+        .transact()
+        .await?;
     let unsorted_orders_json = unsorted_orders.json::<Vec<Order>>()?;
 
     assert_eq!(unsorted_orders_json.len(), 2);
@@ -109,7 +121,11 @@ async fn test_settle_auction_when_user_buy_almost_all_tokens_but_offer_price_is_
 
     let _ = settle_auction(&contract).await?;
 
-    let sorted_orders = contract.call("get_orders").transact().await?;
+    let sorted_orders = contract
+        .call("get_orders")
+        .args_json(serde_json::json!({}))
+        .transact()
+        .await?;
     let sorted_orders_json = sorted_orders.json::<Vec<Order>>()?;
 
     assert_eq!(sorted_orders_json.len(), 2);
@@ -135,9 +151,10 @@ async fn test_settle_auction_when_user_buy_almost_all_tokens_but_offer_price_is_
         winners_json[1].0.buy_amount.parse::<u128>().unwrap(),
         10_u128
     );
+    // Partially filled order keeps the whole deposit, the unfilled part is returned on claim
     assert_eq!(
         winners_json[1].0.sell_amount.parse::<u128>().unwrap(),
-        10_000_000_000_000_000_000_000_000
+        20_000_000_000_000_000_000_000_000
     );
 
     let final_auction_price = contract.call("get_final_auction_price").transact().await?;
@@ -166,7 +183,7 @@ async fn test_settle_auction_when_user_buy_almost_all_tokens_but_offer_price_is_
     let now = Utc::now().timestamp();
     let init_args = ContractBuilder::new(root.id().to_string())
         .total_supply(10)
-        .auction_duration(((now + 600) as u64) * 1000000000)
+        .auction_end(((now + 600) as u64) * 1000000000)
         .min_buy_amount(NearToken::from_near(100).as_yoctonear())
         .build();
     init_contract(&contract, init_args).await?;
@@ -177,7 +194,11 @@ async fn test_settle_auction_when_user_buy_almost_all_tokens_but_offer_price_is_
     let _ = place_order(&alice, &contract, 1, NearToken::from_near(10)).await?;
     let _ = place_order(&bob, &contract, 10, NearToken::from_near(500)).await?;
 
-    let unsorted_orders = contract.call("get_orders").transact().await?;
+    let unsorted_orders = contract
+        .call("get_orders")
+        .args_json(serde_json::json!({}))
+        .transact()
+        .await?;
     let unsorted_orders_json = unsorted_orders.json::<Vec<Order>>()?;
 
     assert_eq!(unsorted_orders_json.len(), 2);
@@ -189,12 +210,17 @@ async fn test_settle_auction_when_user_buy_almost_all_tokens_but_offer_price_is_
     let settle_auction = settle_auction(&contract).await?;
     assert!(settle_auction.is_success());
 
-    let sorted_orders = contract.call("get_orders").transact().await?;
-    let sorted_orders_json = sorted_orders.json::<Vec<Order>>()?;
+    // Settlement sorts its own book, get_orders keeps the placement order
+    let orders = contract
+        .call("get_orders")
+        .args_json(serde_json::json!({}))
+        .transact()
+        .await?;
+    let orders_json = orders.json::<Vec<Order>>()?;
 
-    assert_eq!(sorted_orders_json.len(), 2);
-    assert_eq!(sorted_orders_json[0].bidder, "bob.test.near");
-    assert_eq!(sorted_orders_json[1].bidder, "alice.test.near");
+    assert_eq!(orders_json.len(), 2);
+    assert_eq!(orders_json[0].bidder, "alice.test.near");
+    assert_eq!(orders_json[1].bidder, "bob.test.near");
 
     let winners = winner_list(&contract).await?;
     let winners_json = winners.unwrap().json::<Vec<(Order, bool)>>()?;
@@ -236,7 +262,7 @@ async fn test_settle_auction_when_user_buy_almost_all_tokens_but_offer_price_is_
     let now = Utc::now().timestamp();
     let init_args = ContractBuilder::new(root.id().to_string())
         .total_supply(10)
-        .auction_duration(((now + 600) as u64) * 1000000000)
+        .auction_end(((now + 600) as u64) * 1000000000)
         .min_buy_amount(NearToken::from_near(100).as_yoctonear())
         .build();
     init_contract(&contract, init_args).await?;
@@ -247,7 +273,11 @@ async fn test_settle_auction_when_user_buy_almost_all_tokens_but_offer_price_is_
     let _ = place_order(&alice, &contract, 1, NearToken::from_near(10)).await?;
     let _ = place_order(&bob, &contract, 8, NearToken::from_near(400)).await?;
 
-    let unsorted_orders = contract.call("get_orders").transact().await?;
+    let unsorted_orders = contract
+        .call("get_orders")
+        .args_json(serde_json::json!({}))
+        .transact()
+        .await?;
     let unsorted_orders_json = unsorted_orders.json::<Vec<Order>>()?;
 
     assert_eq!(unsorted_orders_json.len(), 2);
@@ -259,12 +289,17 @@ async fn test_settle_auction_when_user_buy_almost_all_tokens_but_offer_price_is_
     let settle_auction = settle_auction(&contract).await?;
     assert!(settle_auction.is_success());
 
-    let sorted_orders = contract.call("get_orders").transact().await?;
-    let sorted_orders_json = sorted_orders.json::<Vec<Order>>()?;
+    // Settlement sorts its own book, get_orders keeps the placement order
+    let orders = contract
+        .call("get_orders")
+        .args_json(serde_json::json!({}))
+        .transact()
+        .await?;
+    let orders_json = orders.json::<Vec<Order>>()?;
 
-    assert_eq!(sorted_orders_json.len(), 2);
-    assert_eq!(sorted_orders_json[0].bidder, "bob.test.near");
-    assert_eq!(sorted_orders_json[1].bidder, "alice.test.near");
+    assert_eq!(orders_json.len(), 2);
+    assert_eq!(orders_json[0].bidder, "alice.test.near");
+    assert_eq!(orders_json[1].bidder, "bob.test.near");
 
     let winners = winner_list(&contract).await?;
     let winners_json = winners.unwrap().json::<Vec<(Order, bool)>>()?;
@@ -299,3 +334,97 @@ async fn test_settle_auction_when_user_buy_almost_all_tokens_but_offer_price_is_
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_settle_auction_ranks_bids_with_equal_f64_price(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = near_workspaces::sandbox().await?;
+    let root = sandbox.root_account()?;
+
+    let alice = create_subaccount(&root, "alice").await?;
+    let bob = create_subaccount(&root, "bob").await?;
+    let contract_account = create_subaccount(&root, "contract").await?;
+
+    let contract_wasm = near_workspaces::compile_project("./").await?;
+    let contract = contract_account.deploy(&contract_wasm).await?.unwrap();
+
+    let one_near = NearToken::from_near(1).as_yoctonear();
+    let now = Utc::now().timestamp();
+    let init_args = ContractBuilder::new(root.id().to_string())
+        .total_supply(one_near)
+        .auction_end(((now + 600) as u64) * 1000000000)
+        .min_buy_amount(one_near)
+        .build();
+    init_contract(&contract, init_args).await?;
+
+    let _ = register_token_account(&alice, &contract).await?;
+    let _ = register_token_account(&bob, &contract).await?;
+
+    // Both orders buy the whole supply, alice pays 1 yocto more which f64 can not represent
+    let _ = place_order(&bob, &contract, one_near, NearToken::from_near(10)).await?;
+    let _ = place_order(
+        &alice,
+        &contract,
+        one_near,
+        NearToken::from_yoctonear(10 * one_near + 1),
+    )
+    .await?;
+
+    sandbox.fast_forward(2000).await?;
+
+    let _ = settle_auction(&contract).await?;
+
+    let winners = winner_list(&contract).await?;
+    let winners_json = winners.unwrap().json::<Vec<(Order, bool)>>()?;
+
+    assert_eq!(winners_json.len(), 1);
+    assert_eq!(winners_json[0].0.bidder, "alice.test.near");
+    assert_eq!(
+        winners_json[0].0.buy_amount.parse::<u128>().unwrap(),
+        one_near
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_settle_auction_in_steps() -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = near_workspaces::sandbox().await?;
+    let root = sandbox.root_account()?;
+
+    let alice = create_subaccount(&root, "alice").await?;
+    let bob = create_subaccount(&root, "bob").await?;
+    let contract_account = create_subaccount(&root, "contract").await?;
+
+    let contract_wasm = near_workspaces::compile_project("./").await?;
+    let contract = contract_account.deploy(&contract_wasm).await?.unwrap();
+
+    let now = Utc::now().timestamp();
+    let init_args = ContractBuilder::new(root.id().to_string())
+        .total_supply(100)
+        .auction_end(((now + 600) as u64) * 1000000000)
+        .min_buy_amount(NearToken::from_near(50).as_yoctonear())
+        .build();
+    init_contract(&contract, init_args).await?;
+
+    let _ = register_token_account(&alice, &contract).await?;
+    let _ = register_token_account(&bob, &contract).await?;
+
+    let _ = place_order(&alice, &contract, 1, NearToken::from_near(10)).await?;
+    let _ = place_order(&bob, &contract, 2, NearToken::from_near(10)).await?;
+
+    sandbox.fast_forward(2000).await?;
+
+    // One winning order per call
+    assert!(!settle_auction_step(&contract, 1).await?);
+    assert!(settle_auction_step(&contract, 1).await?);
+
+    let winners = winner_list(&contract).await?;
+    let winners_json = winners.unwrap().json::<Vec<(Order, bool)>>()?;
+
+    assert_eq!(winners_json.len(), 2);
+    assert_eq!(winners_json[0].0.bidder, "alice.test.near");
+    assert_eq!(winners_json[1].0.bidder, "bob.test.near");
+
+    Ok(())
+}
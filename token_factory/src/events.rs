@@ -1,3 +1,4 @@
+use crate::AuctionMode;
 use near_sdk::json_types::{Base64VecU8, U128};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::AccountId;
@@ -9,7 +10,6 @@ use std::fmt;
 #[serde(rename_all = "snake_case")]
 #[serde(crate = "near_sdk::serde")]
 #[non_exhaustive]
-
 pub enum EventLogVariant {
     RegisterToken(Vec<RegisterTokenLog>),
 }
@@ -38,15 +38,20 @@ pub struct RegisterTokenLog {
     pub decimals: u8,
     pub image: String,
     pub description: String,
-    pub auction_duration: U64,
+    pub auction_start: Option<U64>,
+    pub auction_end: Option<U64>,
+    pub auction_duration: Option<U64>,
     pub min_buy_amount: NearToken,
+    pub fee_bps: u16, // protocol fee taken from the proceeds of the auction
+    pub fee_recipient: AccountId,
+    pub auction_mode: AuctionMode,
 }
 
 impl fmt::Display for EventLog {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_fmt(format_args!(
             "EVENT_JSON:{}",
-            &serde_json::to_string(self).map_err(|_| fmt::Error)?
+            serde_json::to_string(self).map_err(|_| fmt::Error)?
         ))
     }
 }
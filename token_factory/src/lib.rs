@@ -2,11 +2,11 @@ use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{LookupMap, UnorderedMap};
 
-use near_sdk::json_types::{U128, U64};
+use near_sdk::json_types::{Base64VecU8, U128, U64};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
-    env, env::storage_byte_cost, json_types::Base64VecU8, near_bindgen, AccountId, BorshStorageKey,
-    Gas, PanicOnDefault, Promise, StorageUsage,
+    env, env::storage_byte_cost, near_bindgen, AccountId, BorshStorageKey, Gas, PanicOnDefault,
+    Promise, StorageUsage,
 };
 use near_sdk::{log, serde_json, NearToken, PromiseResult};
 
@@ -17,6 +17,7 @@ pub const ZERO_TOKEN: NearToken = NearToken::from_yoctonear(0);
 const FT_WASM_CODE: &[u8] = include_bytes!("../../token_factory/base_token/base_token.wasm");
 
 const EXTRA_BYTES: usize = 10000;
+const MAX_FEE_BPS: u16 = 10_000;
 const GAS: Gas = Gas::from_gas(50 * 1_000_000_000_000);
 type TokenId = String;
 
@@ -44,6 +45,8 @@ pub struct TokenFactory {
     pub storage_balance_cost: StorageUsage, // Count the amount of storage used by a contract.
     pub default_total_supply: U128,
     pub owner_id: AccountId, // owner of the factory who is the creator of the contract
+    pub protocol_fee_bps: u16, // fee taken from the proceeds of every auction, in basis points
+    pub fee_recipient: Option<AccountId>, // receives the fees instead of the owner if set
 }
 
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
@@ -54,8 +57,127 @@ pub struct TokenArgs {
     pub metadata: FungibleTokenMetadata, // metadata of the token includes name, symbol, decimals, etc.
     pub image: String,                   // image of the token
     pub description: String,             // description of the token
-    pub auction_duration: U64,
+    pub auction: AuctionConfig,          // auction of the token, passed as is to the token contract
+}
+
+// Mirrors the auction config of the base token
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AuctionConfig {
+    pub schedule: AuctionSchedule, // start and end of the auction of the token
     pub min_buy_amount: NearToken,
+    #[serde(default)]
+    pub options: AuctionOptions,
+}
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AuctionOptions {
+    pub min_raise: Option<NearToken>,
+    pub unsold_supply: Option<UnsoldSupply>, // tokens are returned to the auctioner if not set
+    pub protocol_fee: Option<ProtocolFee>,   // set by the factory, a creator can not choose it
+    // Bids are placed in this NEP-141 token with ft_transfer_call instead of NEAR
+    pub quote_token: Option<AccountId>,
+    pub sealed_bid: Option<SealedBid>, // bids are committed as hashes and revealed once bidding closes
+    pub mode: Option<AuctionMode>,     // batch auction if not set
+    pub creator_allocation: Option<CreatorAllocation>, // minted on top of the auctioned supply
+    pub allowlist_root: Option<Base64VecU8>, // only the accounts of the allowlist can bid if set
+    pub max_buy_per_account: Option<U128>,
+    pub soft_close: Option<SoftClose>, // bids at the end of the auction push its end out
+}
+
+#[derive(
+    Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq, Eq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum UnsoldSupply {
+    ReturnToAuctioner,
+    Burn,
+    KeepInTreasury,
+}
+
+// Timestamps in nanoseconds, penalty_bps is kept from a commitment that is never revealed
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SealedBid {
+    pub reveal_duration: U64,
+    pub penalty_bps: u16,
+}
+
+// Vests from the end of the auction, cliff and duration in nanoseconds
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CreatorAllocation {
+    pub amount: U128,
+    pub cliff: U64,
+    pub duration: U64,
+}
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SoftClose {
+    pub window: U64,
+    pub extension: U64,
+    pub max_extension: U64,
+}
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProtocolFee {
+    pub fee_bps: u16,
+    pub recipient: AccountId,
+}
+
+// How the tokens are sold, mirrors the auction modes of the base token
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AuctionMode {
+    Batch, // uniform price auction settled once bidding closes
+    Dutch(DutchAuction),
+    BondingCurve(BondingCurve), // tokens are bought and sold back with buy and sell
+}
+
+// The price falls from start_buy_amount to min_buy_amount for the whole supply
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DutchAuction {
+    pub start_buy_amount: NearToken,
+    pub curve: PriceCurve,
+}
+
+#[derive(
+    Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq, Eq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum PriceCurve {
+    Linear,
+    Exponential { half_life: U64 },
+}
+
+// start_buy_amount for the whole supply at the price of the first token
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BondingCurve {
+    pub start_buy_amount: NearToken,
+    pub shape: CurveShape,
+}
+
+#[derive(
+    Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq, Eq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum CurveShape {
+    Linear { end_buy_amount: NearToken },
+    Exponential { doubling_amount: U128 },
+}
+
+// Timestamps in nanoseconds. The end of the auction is given either as a timestamp or as a duration from the start
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AuctionSchedule {
+    pub start: Option<U64>, // bidding opens as soon as the token is created if not set
+    pub end: Option<U64>,
+    pub duration: Option<U64>,
 }
 
 #[allow(clippy::use_self)]
@@ -69,6 +191,34 @@ impl TokenFactory {
             storage_balance_cost: 0,
             default_total_supply: U128::from(1_000_000_000),
             owner_id: env::predecessor_account_id(),
+            protocol_fee_bps: 0,
+            fee_recipient: None,
+        }
+    }
+
+    // The fee applies to the tokens created from now on, the auctions already created keep their fee
+    pub fn set_protocol_fee(&mut self, fee_bps: u16, fee_recipient: Option<AccountId>) {
+        assert!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only the owner can set the protocol fee"
+        );
+        assert!(
+            fee_bps <= MAX_FEE_BPS,
+            "Protocol fee can not be more than {} basis points",
+            MAX_FEE_BPS
+        );
+
+        self.protocol_fee_bps = fee_bps;
+        self.fee_recipient = fee_recipient;
+    }
+
+    pub fn get_protocol_fee(&self) -> ProtocolFee {
+        ProtocolFee {
+            fee_bps: self.protocol_fee_bps,
+            recipient: self
+                .fee_recipient
+                .clone()
+                .unwrap_or_else(|| self.owner_id.clone()),
         }
     }
 
@@ -77,7 +227,7 @@ impl TokenFactory {
         self.storage_deposits.insert(&account_id, &ZERO_TOKEN);
         self.storage_balance_cost = env::storage_usage() - initial_storage_usage;
         self.storage_deposits.remove(&account_id);
-        return self.storage_balance_cost;
+        self.storage_balance_cost
     }
 
     fn get_min_attached_balance(&self, args: &TokenArgs) -> NearToken {
@@ -133,31 +283,39 @@ impl TokenFactory {
     }
 
     #[payable]
-    #[allow(clippy::too_many_arguments)]
     pub fn create_token(
         &mut self,
-        spec: String,
-        name: String,
-        symbol: String,
-        icon: Option<String>,
-        reference: Option<String>,
-        reference_hash: Option<Base64VecU8>,
-        decimals: u8,
+        metadata: FungibleTokenMetadata,
         image: String,
         description: String,
-        auction_duration: U64,
-        min_buy_amount: NearToken,
+        auction: AuctionConfig,
     ) -> Promise {
         let owner_id = env::predecessor_account_id();
 
-        let token_id = symbol.to_ascii_lowercase();
+        let token_id = metadata.symbol.to_ascii_lowercase();
         assert!(is_valid_token_id(&token_id), "Invalid Symbol");
 
+        assert!(
+            auction.schedule.end.is_some() != auction.schedule.duration.is_some(),
+            "Either the auction end or the auction duration must be set"
+        );
+        assert!(
+            auction.options.protocol_fee.is_none(),
+            "The protocol fee is set by the factory"
+        );
+        let auction = AuctionConfig {
+            options: AuctionOptions {
+                protocol_fee: Some(self.get_protocol_fee()),
+                ..auction.options
+            },
+            ..auction
+        };
+
         assert!(
             !self
                 .tokens
                 .values()
-                .any(|token| token.metadata.name == name),
+                .any(|token| token.metadata.name == metadata.name),
             "A token with this name already exists"
         );
 
@@ -171,19 +329,10 @@ impl TokenFactory {
         let args = TokenArgs {
             owner_id: owner_id.clone(),
             total_supply: self.default_total_supply,
-            metadata: FungibleTokenMetadata {
-                spec,
-                name,
-                symbol,
-                icon,
-                reference,
-                reference_hash,
-                decimals,
-            },
+            metadata,
             image,
             description,
-            auction_duration,
-            min_buy_amount,
+            auction,
         };
 
         if env::attached_deposit() > ZERO_TOKEN {
@@ -192,10 +341,7 @@ impl TokenFactory {
         args.metadata.assert_valid();
 
         let required_balance = self.get_min_attached_balance(&args);
-        let user_balance = self
-            .storage_deposits
-            .get(&owner_id)
-            .map_or(ZERO_TOKEN, |balance| balance);
+        let user_balance = self.storage_deposits.get(&owner_id).unwrap_or(ZERO_TOKEN);
 
         assert!(
             user_balance >= required_balance,
@@ -233,59 +379,37 @@ impl TokenFactory {
             .then(
                 Self::ext(env::current_account_id())
                     .with_static_gas(GAS)
-                    .on_create_token_callback(
-                        args.owner_id,
-                        args.total_supply,
-                        args.metadata.spec,
-                        args.metadata.name,
-                        args.metadata.symbol,
-                        args.metadata.icon,
-                        args.metadata.reference,
-                        args.metadata.reference_hash,
-                        args.metadata.decimals,
-                        args.image,
-                        args.description,
-                        args.auction_duration,
-                        args.min_buy_amount,
-                    ),
+                    .on_create_token_callback(args),
             )
     }
 
     #[private]
-    pub fn on_create_token_callback(
-        &mut self,
-        owner_id: AccountId,
-        total_supply: U128,
-        spec: String,
-        name: String,
-        symbol: String,
-        icon: Option<String>,
-        reference: Option<String>,
-        reference_hash: Option<Base64VecU8>,
-        decimals: u8,
-        image: String,
-        description: String,
-        auction_duration: U64,
-        min_buy_amount: NearToken,
-    ) {
+    pub fn on_create_token_callback(&mut self, args: TokenArgs) {
         if let PromiseResult::Successful(_) = env::promise_result(0) {
+            let schedule = args.auction.schedule;
+            let protocol_fee = args.auction.options.protocol_fee.unwrap();
             let register_token_log = EventLog {
                 standard: "nep141".to_string(),
                 version: "1.0.0".to_string(),
                 event: EventLogVariant::RegisterToken(vec![RegisterTokenLog {
-                    owner_id,
-                    total_supply,
-                    spec,
-                    name,
-                    symbol,
-                    icon,
-                    reference,
-                    reference_hash,
-                    decimals,
-                    image,
-                    description,
-                    auction_duration,
-                    min_buy_amount,
+                    owner_id: args.owner_id,
+                    total_supply: args.total_supply,
+                    spec: args.metadata.spec,
+                    name: args.metadata.name,
+                    symbol: args.metadata.symbol,
+                    icon: args.metadata.icon,
+                    reference: args.metadata.reference,
+                    reference_hash: args.metadata.reference_hash,
+                    decimals: args.metadata.decimals,
+                    image: args.image,
+                    description: args.description,
+                    auction_start: schedule.start,
+                    auction_end: schedule.end,
+                    auction_duration: schedule.duration,
+                    min_buy_amount: args.auction.min_buy_amount,
+                    fee_bps: protocol_fee.fee_bps,
+                    fee_recipient: protocol_fee.recipient,
+                    auction_mode: args.auction.options.mode.unwrap_or(AuctionMode::Batch),
                 }]),
             };
 
@@ -340,8 +464,21 @@ mod test {
             },
             image,
             description,
-            auction_duration: U64::from((now + 600) as u64 * 1000000000),
-            min_buy_amount: NearToken::from_near(50),
+            auction: AuctionConfig {
+                schedule: AuctionSchedule {
+                    start: None,
+                    end: Some(U64::from((now + 600) as u64 * 1000000000)),
+                    duration: None,
+                },
+                min_buy_amount: NearToken::from_near(50),
+                options: AuctionOptions {
+                    protocol_fee: Some(ProtocolFee {
+                        fee_bps: 0,
+                        recipient: "factory.near".parse().unwrap(),
+                    }),
+                    ..Default::default()
+                },
+            },
         }
     }
 
@@ -359,6 +496,6 @@ mod test {
             "This is a test token".to_string(),
         );
         let result = contract.get_min_attached_balance(&args);
-        assert_eq!(result, NearToken::from_yoctonear(2650350000000000000000000));
+        assert_eq!(result, NearToken::from_yoctonear(4690850000000000000000000));
     }
 }
@@ -5,7 +5,7 @@ use near_sdk::{
     AccountId, NearToken as SdkNearToken,
 };
 use near_workspaces::types::NearToken as WorkspacesNearToken;
-use token_factory::TokenArgs;
+use token_factory::{AuctionConfig, AuctionOptions, AuctionSchedule, TokenArgs};
 
 pub const HUNDRED_NEAR: WorkspacesNearToken = WorkspacesNearToken::from_near(100);
 const DATA_IMAGE_SVG_NEAR_ICON: &str = "data:image/icon";
@@ -32,8 +32,15 @@ pub fn new_default_token_args(
         },
         image,
         description,
-        auction_duration: U64::from((now + 600) as u64 * 1000000000),
-        min_buy_amount: SdkNearToken::from_yoctonear(WorkspacesNearToken::from_near(50).as_yoctonear()),
+        auction: AuctionConfig {
+            schedule: AuctionSchedule {
+                start: None,
+                end: Some(U64::from((now + 600) as u64 * 1000000000)),
+                duration: None,
+            },
+            min_buy_amount: SdkNearToken::from_yoctonear(WorkspacesNearToken::from_near(50).as_yoctonear()),
+            options: AuctionOptions::default(),
+        },
     }
 }
 
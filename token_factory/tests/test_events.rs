@@ -1,16 +1,16 @@
 use chrono::Utc;
 use near_sdk::json_types::{U128, U64};
 use near_sdk::NearToken;
-use token_factory::{EventLog, EventLogVariant, RegisterTokenLog};
+use token_factory::{AuctionMode, EventLog, EventLogVariant, RegisterTokenLog};
 
 #[test]
 fn test_register_token() {
     let now = Utc::now().timestamp();
-    let auction_duration = U64::from((now + 600) as u64 * 1000000000);
+    let auction_end = U64::from((now + 600) as u64 * 1000000000);
 
     let expected = format!(
-        r#"EVENT_JSON:{{"standard":"nep141","version":"1.0.0","event":"register_token","data":[{{"owner_id":"token.near","total_supply":"100","spec":"ft-1.0.0","name":"token","symbol":"tk","icon":"data:image/icon","reference":null,"reference_hash":null,"decimals":24,"image":"data:image/icon","description":"Cool token","auction_duration":"{:?}","min_buy_amount":"50000000000000000000000000"}}]}}"#,
-        auction_duration.0
+        r#"EVENT_JSON:{{"standard":"nep141","version":"1.0.0","event":"register_token","data":[{{"owner_id":"token.near","total_supply":"100","spec":"ft-1.0.0","name":"token","symbol":"tk","icon":"data:image/icon","reference":null,"reference_hash":null,"decimals":24,"image":"data:image/icon","description":"Cool token","auction_start":null,"auction_end":"{:?}","auction_duration":null,"min_buy_amount":"50000000000000000000000000","fee_bps":100,"fee_recipient":"factory.near","auction_mode":"Batch"}}]}}"#,
+        auction_end.0
     );
 
     let log = EventLog {
@@ -28,12 +28,17 @@ fn test_register_token() {
             decimals: 24,
             image: "data:image/icon".to_string(),
             description: "Cool token".to_string(),
-            auction_duration,
+            auction_start: None,
+            auction_end: Some(auction_end),
+            auction_duration: None,
             min_buy_amount: NearToken::from_near(50),
+            fee_bps: 100,
+            fee_recipient: "factory.near".parse().unwrap(),
+            auction_mode: AuctionMode::Batch,
         }]),
     };
     println!("\nEXPECTED: {}", expected);
-    println!("\nLOG: {}", log.to_string());
+    println!("\nLOG: {}", log);
 
     assert_eq!(expected, log.to_string());
 }
@@ -5,7 +5,7 @@ use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
 use near_sdk::{json_types::U128, json_types::U64, AccountId, NearToken as SdkNearToken};
 use near_workspaces::types::NearToken as WorkspacesNearToken;
 use serde_json::json;
-use token_factory::TokenArgs;
+use token_factory::{AuctionConfig, AuctionOptions, AuctionSchedule, TokenArgs};
 
 pub mod common;
 
@@ -49,17 +49,10 @@ async fn test_create_one_token() -> anyhow::Result<()> {
     let outcome_create_token = token_account
         .call(contract.id(), "create_token")
         .args_json(json!({
-            "spec": args.metadata.spec ,
-            "name": args.metadata.name ,
-            "symbol":args.metadata.symbol ,
-            "icon":args.metadata.icon,
-            "reference": args.metadata.reference,
-            "reference_hash": args.metadata.reference_hash,
-            "decimals":args.metadata.decimals,
+            "metadata": args.metadata,
             "image": args.image,
             "description": args.description,
-            "auction_duration" : args.auction_duration,
-            "min_buy_amount" : args.min_buy_amount,
+            "auction": args.auction,
         }))
         .deposit(deposit)
         .max_gas()
@@ -69,7 +62,7 @@ async fn test_create_one_token() -> anyhow::Result<()> {
     assert!(outcome_create_token.is_success());
 
     let expected = format!(
-        r#"EVENT_JSON:{{"standard":"nep141","version":"1.0.0","event":"register_token","data":[{{"owner_id":"{}","total_supply":"{:?}","spec":"ft-1.0.0","name":"{}","symbol":"{}","icon":{:?},"reference":null,"reference_hash":null,"decimals":24,"image":"{}","description":"{}","auction_duration":"{}","min_buy_amount":"50000000000000000000000000"}}]}}"#,
+        r#"EVENT_JSON:{{"standard":"nep141","version":"1.0.0","event":"register_token","data":[{{"owner_id":"{}","total_supply":"{:?}","spec":"ft-1.0.0","name":"{}","symbol":"{}","icon":{:?},"reference":null,"reference_hash":null,"decimals":24,"image":"{}","description":"{}","auction_start":null,"auction_end":"{}","auction_duration":null,"min_buy_amount":"50000000000000000000000000","fee_bps":0,"fee_recipient":"{}","auction_mode":"Batch"}}]}}"#,
         token_account.id(),
         U128::from(1_000_000_000).0,
         args.metadata.name,
@@ -77,7 +70,8 @@ async fn test_create_one_token() -> anyhow::Result<()> {
         DATA_IMAGE_SVG_NEAR_ICON,
         args.image,
         args.description,
-        args.auction_duration.0,
+        args.auction.schedule.end.unwrap().0,
+        contract.id(),
     );
 
     let logs = outcome_create_token.logs();
@@ -168,19 +162,11 @@ async fn test_create_multiple_tokens() -> anyhow::Result<()> {
     //@dev Create token
     let outcome_create_token = token_account
         .call(contract.id(), "create_token")
-        .args_json(json!({"owner_id": args.owner_id,
-            "total_supply":args.total_supply,
-            "spec": args.metadata.spec ,
-            "name": args.metadata.name ,
-            "symbol":args.metadata.symbol ,
-            "icon":args.metadata.icon,
-            "reference": args.metadata.reference,
-            "reference_hash": args.metadata.reference_hash,
-            "decimals":args.metadata.decimals,
+        .args_json(json!({
+            "metadata": args.metadata,
             "image": args.image,
             "description": args.description,
-            "auction_duration" : args.auction_duration,
-            "min_buy_amount" : args.min_buy_amount,
+            "auction": args.auction,
         }))
         .deposit(deposit)
         .max_gas()
@@ -190,7 +176,7 @@ async fn test_create_multiple_tokens() -> anyhow::Result<()> {
     println!("create_token outcome: {:#?}", outcome_create_token);
 
     let expected = format!(
-        r#"EVENT_JSON:{{"standard":"nep141","version":"1.0.0","event":"register_token","data":[{{"owner_id":"{}","total_supply":"{:?}","spec":"ft-1.0.0","name":"{}","symbol":"{}","icon":{:?},"reference":null,"reference_hash":null,"decimals":24,"image":"{}","description":"{}","auction_duration":"{}","min_buy_amount":"50000000000000000000000000"}}]}}"#,
+        r#"EVENT_JSON:{{"standard":"nep141","version":"1.0.0","event":"register_token","data":[{{"owner_id":"{}","total_supply":"{:?}","spec":"ft-1.0.0","name":"{}","symbol":"{}","icon":{:?},"reference":null,"reference_hash":null,"decimals":24,"image":"{}","description":"{}","auction_start":null,"auction_end":"{}","auction_duration":null,"min_buy_amount":"50000000000000000000000000","fee_bps":0,"fee_recipient":"{}","auction_mode":"Batch"}}]}}"#,
         token_account.id(),
         U128::from(1_000_000_000).0,
         args.metadata.name,
@@ -198,7 +184,8 @@ async fn test_create_multiple_tokens() -> anyhow::Result<()> {
         DATA_IMAGE_SVG_NEAR_ICON,
         args.image,
         args.description,
-        args.auction_duration.0,
+        args.auction.schedule.end.unwrap().0,
+        contract.id(),
     );
 
     let logs = outcome_create_token.logs();
@@ -273,25 +260,24 @@ async fn test_create_multiple_tokens() -> anyhow::Result<()> {
         },
         image: DATA_IMAGE_SVG_NEAR_ICON.to_string(),
         description: "This is a test token".to_string(),
-        auction_duration: U64::from((now + 600) as u64 * 1000000000),
-        min_buy_amount: SdkNearToken::from_near(50),
+        auction: AuctionConfig {
+            schedule: AuctionSchedule {
+                start: None,
+                end: Some(U64::from((now + 600) as u64 * 1000000000)),
+                duration: None,
+            },
+            min_buy_amount: SdkNearToken::from_near(50),
+            options: AuctionOptions::default(),
+        },
     };
 
     let outcome_create_token_2 = token_account_2
         .call(contract.id(), "create_token")
-        .args_json(json!({"owner_id": args.owner_id,
-            "total_supply":args.total_supply,
-            "spec": args.metadata.spec ,
-            "name": args.metadata.name ,
-            "symbol":args.metadata.symbol ,
-            "icon":args.metadata.icon,
-            "reference": args.metadata.reference,
-            "reference_hash": args.metadata.reference_hash,
-            "decimals":args.metadata.decimals,
+        .args_json(json!({
+            "metadata": args.metadata,
             "image": args.image,
             "description": args.description,
-            "auction_duration": args.auction_duration,
-            "min_buy_amount": args.min_buy_amount
+            "auction": args.auction,
         }))
         .deposit(deposit)
         .max_gas()
@@ -300,7 +286,7 @@ async fn test_create_multiple_tokens() -> anyhow::Result<()> {
 
     println!("create_token outcome 2: {:#?}", outcome_create_token_2);
     let expected = format!(
-        r#"EVENT_JSON:{{"standard":"nep141","version":"1.0.0","event":"register_token","data":[{{"owner_id":"{}","total_supply":"{:?}","spec":"ft-1.0.0","name":"{}","symbol":"{}","icon":{:?},"reference":null,"reference_hash":null,"decimals":24,"image":"{}","description":"{}","auction_duration":"{}","min_buy_amount":"50000000000000000000000000"}}]}}"#,
+        r#"EVENT_JSON:{{"standard":"nep141","version":"1.0.0","event":"register_token","data":[{{"owner_id":"{}","total_supply":"{:?}","spec":"ft-1.0.0","name":"{}","symbol":"{}","icon":{:?},"reference":null,"reference_hash":null,"decimals":24,"image":"{}","description":"{}","auction_start":null,"auction_end":"{}","auction_duration":null,"min_buy_amount":"50000000000000000000000000","fee_bps":0,"fee_recipient":"{}","auction_mode":"Batch"}}]}}"#,
         token_account_2.id(),
         U128::from(1_000_000_000).0,
         args.metadata.name,
@@ -308,7 +294,8 @@ async fn test_create_multiple_tokens() -> anyhow::Result<()> {
         DATA_IMAGE_SVG_NEAR_ICON,
         args.image,
         args.description,
-        args.auction_duration.0,
+        args.auction.schedule.end.unwrap().0,
+        contract.id(),
     );
 
     let logs = outcome_create_token_2.logs();
@@ -1,9 +1,13 @@
 use chrono::Utc;
 use common::utils::new_default_token_args;
 use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_sdk::json_types::{Base64VecU8, U128, U64};
 use near_sdk::test_utils::{accounts, VMContextBuilder};
-use near_sdk::{json_types::U128, json_types::U64, testing_env, AccountId, NearToken};
-use token_factory::{TokenArgs, TokenFactory};
+use near_sdk::{testing_env, AccountId, NearToken};
+use token_factory::{
+    AuctionConfig, AuctionMode, AuctionOptions, AuctionSchedule, CreatorAllocation, DutchAuction,
+    PriceCurve, SealedBid, SoftClose, TokenArgs, TokenFactory, UnsoldSupply,
+};
 
 pub mod common;
 
@@ -60,17 +64,10 @@ fn test_create_token_and_get_token() {
     );
 
     contract.create_token(
-        token_args.metadata.spec.clone(),
-        token_args.metadata.name.clone(),
-        token_args.metadata.symbol.clone(),
-        None,
-        None,
-        None,
-        token_args.metadata.decimals,
+        token_args.metadata.clone(),
         token_args.image.clone(),
         token_args.description.clone(),
-        token_args.auction_duration,
-        token_args.min_buy_amount,
+        token_args.auction,
     );
 
     let (owner, total_supply, metadata, image) =
@@ -95,17 +92,10 @@ fn test_get_number_of_tokens() {
     );
 
     contract.create_token(
-        token_args.metadata.spec.clone(),
-        token_args.metadata.name.clone(),
-        token_args.metadata.symbol.clone(),
-        None,
-        None,
-        None,
-        token_args.metadata.decimals,
-        token_args.image,
-        token_args.description,
-        token_args.auction_duration,
-        token_args.min_buy_amount,
+        token_args.metadata.clone(),
+        token_args.image.clone(),
+        token_args.description.clone(),
+        token_args.auction,
     );
 
     let context = get_context(accounts(1));
@@ -126,24 +116,212 @@ fn test_get_number_of_tokens() {
         },
         image: DATA_IMAGE_SVG_NEAR_ICON.to_string(),
         description: "This is a test token".to_string(),
-        auction_duration: U64::from((now + 600) as u64 * 1000000000),
-        min_buy_amount: NearToken::from_near(50),
+        auction: AuctionConfig {
+            schedule: AuctionSchedule {
+                start: None,
+                end: Some(U64::from((now + 600) as u64 * 1000000000)),
+                duration: None,
+            },
+            min_buy_amount: NearToken::from_near(50),
+            options: AuctionOptions::default(),
+        },
     };
 
     contract.create_token(
-        token_args_2.metadata.spec.clone(),
-        token_args_2.metadata.name.clone(),
-        token_args_2.metadata.symbol.clone(),
-        None,
-        None,
-        None,
-        token_args_2.metadata.decimals,
-        token_args_2.image,
-        token_args_2.description,
-        token_args.auction_duration,
-        token_args.min_buy_amount,
+        token_args_2.metadata.clone(),
+        token_args_2.image.clone(),
+        token_args_2.description.clone(),
+        token_args_2.auction,
     );
 
     let result = contract.get_number_of_tokens();
     assert_eq!(result, 2);
 }
+
+#[test]
+fn test_protocol_fee_is_fixed_into_token_args() {
+    let context = get_context(accounts(0));
+    testing_env!(context.build());
+
+    let mut contract = TokenFactory::new();
+    contract.set_protocol_fee(250, Some(accounts(3)));
+
+    let fee = contract.get_protocol_fee();
+    assert_eq!(fee.fee_bps, 250);
+    assert_eq!(fee.recipient, accounts(3));
+
+    let token_args = new_default_token_args(
+        &accounts(0),
+        U128::from(1000000000),
+        DATA_IMAGE_SVG_NEAR_ICON.to_string(),
+        "This is a test token".to_string(),
+    );
+    contract.create_token(
+        token_args.metadata.clone(),
+        token_args.image.clone(),
+        token_args.description.clone(),
+        token_args.auction,
+    );
+
+    // Later changes do not apply to the tokens already created
+    contract.set_protocol_fee(500, None);
+    assert_eq!(contract.get_protocol_fee().recipient, accounts(0));
+
+    let stored_args = contract
+        .tokens
+        .get(&token_args.metadata.symbol.to_ascii_lowercase())
+        .unwrap();
+    let protocol_fee = stored_args.auction.options.protocol_fee.unwrap();
+    assert_eq!(protocol_fee.fee_bps, 250);
+    assert_eq!(protocol_fee.recipient, accounts(3));
+}
+
+#[test]
+fn test_create_token_in_dutch_mode() {
+    let context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = TokenFactory::new();
+
+    let token_args = new_default_token_args(
+        &accounts(0),
+        U128::from(1000000000),
+        DATA_IMAGE_SVG_NEAR_ICON.to_string(),
+        "This is a test token".to_string(),
+    );
+    let mode = AuctionMode::Dutch(DutchAuction {
+        start_buy_amount: NearToken::from_near(500),
+        curve: PriceCurve::Linear,
+    });
+    contract.create_token(
+        token_args.metadata.clone(),
+        token_args.image.clone(),
+        token_args.description.clone(),
+        AuctionConfig {
+            options: AuctionOptions {
+                mode: Some(mode.clone()),
+                ..Default::default()
+            },
+            ..token_args.auction
+        },
+    );
+
+    let stored_args = contract
+        .tokens
+        .get(&token_args.metadata.symbol.to_ascii_lowercase())
+        .unwrap();
+    assert_eq!(stored_args.auction.options.mode, Some(mode));
+}
+
+#[test]
+fn test_create_token_forwards_auction_options() {
+    let context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = TokenFactory::new();
+
+    let token_args = new_default_token_args(
+        &accounts(0),
+        U128::from(1000000000),
+        DATA_IMAGE_SVG_NEAR_ICON.to_string(),
+        "This is a test token".to_string(),
+    );
+    let sealed_bid = SealedBid {
+        reveal_duration: U64::from(3600 * 1_000_000_000),
+        penalty_bps: 500,
+    };
+    let creator_allocation = CreatorAllocation {
+        amount: U128::from(100000000),
+        cliff: U64::from(86400 * 1_000_000_000),
+        duration: U64::from(30 * 86400 * 1_000_000_000),
+    };
+    let soft_close = SoftClose {
+        window: U64::from(300 * 1_000_000_000),
+        extension: U64::from(300 * 1_000_000_000),
+        max_extension: U64::from(3600 * 1_000_000_000),
+    };
+    contract.create_token(
+        token_args.metadata.clone(),
+        token_args.image.clone(),
+        token_args.description.clone(),
+        AuctionConfig {
+            options: AuctionOptions {
+                min_raise: Some(NearToken::from_near(100)),
+                unsold_supply: Some(UnsoldSupply::Burn),
+                sealed_bid: Some(sealed_bid.clone()),
+                creator_allocation: Some(creator_allocation.clone()),
+                allowlist_root: Some(Base64VecU8::from(vec![7; 32])),
+                max_buy_per_account: Some(U128::from(1000000)),
+                soft_close: Some(soft_close.clone()),
+                ..Default::default()
+            },
+            ..token_args.auction
+        },
+    );
+
+    let stored_args = contract
+        .tokens
+        .get(&token_args.metadata.symbol.to_ascii_lowercase())
+        .unwrap();
+    let options = stored_args.auction.options;
+    assert_eq!(options.min_raise, Some(NearToken::from_near(100)));
+    assert_eq!(options.unsold_supply, Some(UnsoldSupply::Burn));
+    assert_eq!(options.sealed_bid, Some(sealed_bid));
+    assert_eq!(options.creator_allocation, Some(creator_allocation));
+    assert_eq!(options.allowlist_root.unwrap().0, vec![7; 32]);
+    assert_eq!(options.max_buy_per_account, Some(U128::from(1000000)));
+    assert_eq!(options.soft_close, Some(soft_close));
+}
+
+#[test]
+fn test_create_token_with_quote_token() {
+    let context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = TokenFactory::new();
+
+    let token_args = new_default_token_args(
+        &accounts(0),
+        U128::from(1000000000),
+        DATA_IMAGE_SVG_NEAR_ICON.to_string(),
+        "This is a test token".to_string(),
+    );
+    let quote_token: AccountId = "usdc.near".parse().unwrap();
+    contract.create_token(
+        token_args.metadata.clone(),
+        token_args.image.clone(),
+        token_args.description.clone(),
+        AuctionConfig {
+            options: AuctionOptions {
+                quote_token: Some(quote_token.clone()),
+                ..Default::default()
+            },
+            ..token_args.auction
+        },
+    );
+
+    let stored_args = contract
+        .tokens
+        .get(&token_args.metadata.symbol.to_ascii_lowercase())
+        .unwrap();
+    assert_eq!(stored_args.auction.options.quote_token, Some(quote_token));
+    assert!(stored_args.auction.options.protocol_fee.is_some());
+}
+
+#[test]
+#[should_panic(expected = "Only the owner can set the protocol fee")]
+fn test_set_protocol_fee_by_another_account() {
+    let context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = TokenFactory::new();
+
+    let context = get_context(accounts(1));
+    testing_env!(context.build());
+    contract.set_protocol_fee(250, None);
+}
+
+#[test]
+#[should_panic(expected = "Protocol fee can not be more than 10000 basis points")]
+fn test_set_protocol_fee_above_maximum() {
+    let context = get_context(accounts(0));
+    testing_env!(context.build());
+    let mut contract = TokenFactory::new();
+    contract.set_protocol_fee(10_001, None);
+}